@@ -0,0 +1,74 @@
+// leaderboard shown after a game, kept in localStorage
+use std::io;
+
+use dioxus::prelude::*;
+use tetris_core::{format_time, HighScore, HighScores, Ranking, ScoreStorage};
+use web_sys::js_sys::Date;
+
+use crate::settings::local_storage;
+
+pub struct LocalStorage;
+
+impl ScoreStorage for LocalStorage {
+    fn read(&self, key: &str) -> Option<String> {
+        local_storage()?.get_item(key).ok().flatten()
+    }
+
+    fn write(&mut self, key: &str, value: &str) -> io::Result<()> {
+        let storage = local_storage()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::Unsupported, "no localStorage"))?;
+        storage
+            .set_item(key, value)
+            .map_err(|error| io::Error::other(format!("{error:?}"))) // e.g. over the quota
+    }
+}
+
+pub fn now() -> u64 {
+    Date::now() as u64
+}
+
+fn format_date(high_score: &HighScore) -> String {
+    let date = Date::new(&(high_score.date as f64).into());
+    date.to_locale_date_string("default", &Default::default())
+        .into()
+}
+
+#[component]
+pub fn HighScoreTable(
+    cx: Scope,
+    high_scores: UseRef<HighScores>,
+    #[props(!optional)] new_rank: Option<usize>,
+) -> Element<'a> {
+    let ranked_by = match high_scores.read().game_mode().ranking() {
+        Ranking::Score => "score",
+        Ranking::Time => "time",
+    };
+    render! {
+        p { "best {high_scores.read().game_mode().name()} results by {ranked_by}" }
+        table {
+            class: "highscores",
+            tr {
+                th { "#" }
+                th { "score" }
+                th { "lines" }
+                th { "level" }
+                th { "time" }
+                th { "date" }
+                th { "seed" }
+            }
+            for (rank, high_score) in high_scores.read().entries().iter().enumerate() {
+                tr {
+                    class: if Some(rank) == *new_rank {"newhighscore"} else {""},
+                    td { "{rank + 1}" }
+                    td { "{high_score.score}" }
+                    td { "{high_score.lines}" }
+                    td { "{high_score.level}" }
+                    td { "{format_time(high_score.duration)}" }
+                    td { "{format_date(high_score)}" }
+                    // links to the same pieces again
+                    td { a { href: "?seed={high_score.seed}", "{high_score.seed}" } }
+                }
+            }
+        }
+    }
+}
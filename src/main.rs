@@ -1,509 +1,346 @@
 use dioxus::html::input_data::keyboard_types::Code;
 use dioxus::prelude::*;
-use futures_util::stream::StreamExt;
-use std::{fmt::Display, time::Duration}; // for rx.next()
+use std::{rc::Rc, str::FromStr};
 
 // use wasm_bindgen::JsCast;
-use web_sys::{wasm_bindgen::JsCast, EventTarget, HtmlElement};
+use web_sys::{js_sys, wasm_bindgen::JsCast, EventTarget};
 
-use gloo_utils::document;
 use log::LevelFilter;
-use rand::{
-    distributions::{Distribution, Standard},
-    random, Rng,
+mod high_scores;
+mod replay_viewer;
+mod settings;
+use high_scores::{HighScoreTable, LocalStorage};
+use replay_viewer::ReplayViewer;
+use settings::{HandlingSettings, KeyBindingSettings, RuleSettings};
+use tetris_core::{
+    build_board, format_seconds, format_time, param, Action, Board, Bot, FinesseTarget, FrameInput,
+    GameMode, Goal, HighScore, HighScores, Orientation, Piece, PieceType, Press, Ranking, Replay,
+    RotationSystem, FRAME_DURATION, GAME_MODES, MAX_CATCH_UP_FRAMES,
 };
-use tokio::time::timeout;
 
 //
 fn main() {
-    // let mut board = Board::new(8, 4);
-
-    // println!("{}", board);
-
-    // for _ in 0..5 {
-    //     board.tick();
-    //     println!("{}", board);
-    // }
+    // the terminal version is the tetris-tui crate
     dioxus_web::launch(App);
     // dioxus_web::launch(Test);
 
     dioxus_logger::init(LevelFilter::Info).expect("Failed to launch logger");
 }
 
+#[allow(non_snake_case)]
 fn App(cx: Scope) -> Element {
+    // ?mode=sprint skips the mode select
+    let game_mode = use_state(cx, || {
+        url_param::<String>("mode").and_then(|name| {
+            GAME_MODES
+                .into_iter()
+                .find(|mode| mode.name().eq_ignore_ascii_case(&name))
+        })
+    });
+    let watching: &UseState<Option<Rc<Replay>>> = use_state(cx, || None);
+    let replay_error: &UseState<Option<String>> = use_state(cx, || None);
+    let drag_listeners_state = use_state(cx, || None); // just to keep them in scope
+
+    use_on_create(cx, || {
+        to_owned![watching, replay_error, drag_listeners_state];
+        async move {
+            let document_event_target: EventTarget = gloo_utils::document().dyn_into().unwrap();
+            // the browser only allows dropping if dragover is cancelled
+            let drag_over_listener = gloo_events::EventListener::new_with_options(
+                &document_event_target,
+                "dragover",
+                gloo_events::EventListenerOptions::enable_prevent_default(),
+                |event| event.prevent_default(),
+            );
+            let drop_listener = gloo_events::EventListener::new_with_options(
+                &document_event_target,
+                "drop",
+                gloo_events::EventListenerOptions::enable_prevent_default(),
+                move |event| {
+                    event.prevent_default(); // otherwise the browser opens the file
+                    let event = event.dyn_ref::<web_sys::DragEvent>().unwrap();
+                    let Some(file) = event
+                        .data_transfer()
+                        .and_then(|data| data.files())
+                        .and_then(|files| files.get(0))
+                    else {
+                        return;
+                    };
+                    to_owned![watching, replay_error];
+                    wasm_bindgen_futures::spawn_local(async move {
+                        let text = wasm_bindgen_futures::JsFuture::from(file.text())
+                            .await
+                            .ok()
+                            .and_then(|text| text.as_string())
+                            .unwrap_or_default();
+                        match text.parse::<Replay>() {
+                            Ok(replay) => {
+                                replay_error.set(None);
+                                watching.set(Some(Rc::new(replay)));
+                            }
+                            Err(error) => replay_error.set(Some(error)),
+                        }
+                    });
+                },
+            );
+            drag_listeners_state.set(Some((drag_over_listener, drop_listener)));
+        }
+    });
+
     render! {
         link { rel: "stylesheet", href: "https://fonts.googleapis.com/css?family=Sixtyfour" }
         div { class: "mainpage",
             a {href: "/", style: "text-decoration: none; color: var(--purple);", h1 {"Tetris"}}
-            BoardView {}
-        }
-    }
-}
-
-struct Board {
-    board: Vec<Vec<Option<f32>>>, // probably later Option<Color> or something
-    width: usize,
-    height: usize,
-    active_piece: Piece,
-    stored_piece: PieceType,
-    done: bool,
-    score: u32,
-}
-
-fn random_piece_at(x: usize, y: usize) -> Piece {
-    Piece {
-        position: (x as i32, y as i32),
-        piece_type: rand::random(),
-        orientation: Orientation::Deg0,
-    }
-}
-
-impl Board {
-    fn new(width: usize, height: usize) -> Self {
-        Board {
-            board: vec![vec![None; width]; height],
-            width,
-            height,
-            active_piece: random_piece_at(width / 2, height - 4),
-            stored_piece: random(),
-            done: false,
-            score: 0,
-        }
-    }
-
-    fn set_square(&mut self, x: usize, y: usize, hue: f32) {
-        self.board[y][x] = Some(hue);
-    }
-    fn get_square_hue(&self, x: usize, y: usize) -> Option<f32> {
-        self.board[y][x]
-    }
-
-    fn square_filled(&self, x: usize, y: usize) -> bool {
-        self.board[y][x].is_some()
-    }
-    // fn add_piece(&mut self, piece: &Piece) {
-    //     for (x,y) in piece.squares
-    // }
-    fn check_valid_piece_position(&self, piece: &Piece) -> bool {
-        for (x, y) in piece.squares() {
-            if !self.open_square((x, y)) {
-                return false;
-            }
-        }
-        true
-    }
-
-    fn swap_stored(&mut self) {
-        let mut new_active_piece = self.active_piece.clone();
-        new_active_piece.piece_type = self.stored_piece.clone();
-        if self.check_valid_piece_position(&new_active_piece) {
-            // self.stored_piece = self.active_piece.piece_type;
-            // self.active_piece = new_active_piece;
-            let old_active_piece = std::mem::replace(&mut self.active_piece, new_active_piece);
-            self.stored_piece = old_active_piece.piece_type;
-        }
-    }
-
-    fn instant_drop_piece(&self) -> Piece {
-        let mut phantom_piece = self.active_piece.clone();
-        for y in (-1..self.active_piece.position.1).rev() {
-            // check from -1 since turned pieces can have negative y-pos while being inside the board
-            phantom_piece.position.1 = y as i32;
-            if !self.check_valid_piece_position(&phantom_piece) {
-                phantom_piece.position.1 += 1;
-                return phantom_piece;
+            if let Some(replay) = watching.get() {
+                rsx!{ ReplayViewer { replay: replay.clone(), on_quit: move |_| watching.set(None) } }
+            } else if let Some(mode) = game_mode.get() {
+                rsx!{
+                    BoardView {
+                        game_mode: *mode,
+                        on_quit: move |_| game_mode.set(None),
+                        on_watch: move |replay| watching.set(Some(replay))
+                    }
+                }
+            } else {
+                rsx!{
+                    ModeSelect { on_select: move |mode| game_mode.set(Some(mode)) }
+                    RuleSettings {}
+                    p { class: "seed", "drop a .replay file here to watch it" }
+                    if let Some(error) = replay_error.get() {
+                        rsx!{ p { class: "seed", "couldn't read that replay: {error}" } }
+                    }
+                }
             }
         }
-        self.active_piece.clone() // fallback, but shouldn't be necessary
-    }
-
-    fn do_instant_drop(&mut self) {
-        self.active_piece = self.instant_drop_piece();
-        self.tick(); // instantly lock piece, maybe not ideal
-    }
-
-    fn tick(&mut self) {
-        let piece_moved = self.move_piece(Direction::Down);
-        if !piece_moved {
-            self.lock_and_renew_active_piece();
-        }
-        self.clear_full_rows();
     }
+}
 
-    fn move_piece(&mut self, direction: Direction) -> bool {
-        for (x, y) in self.active_piece.squares_after_move(direction.clone()) {
-            if !self.open_square((x, y)) {
-                return false;
+#[component]
+fn ModeSelect<'a>(
+    cx: Scope<'a>,
+    on_select: EventHandler<'a, &'static dyn GameMode>,
+) -> Element<'a> {
+    render! {
+        div {
+            class: "modeselect",
+            for mode in GAME_MODES {
+                button {
+                    onclick: move |_| on_select.call(mode),
+                    h2 { "{mode.name()}" }
+                    p { "{mode.description()}" }
+                }
             }
         }
-        self.active_piece.move_in_direction(direction);
-        true
-    }
-
-    fn in_range(&self, (x, y): (i32, i32)) -> bool {
-        x >= 0 && x < self.width as i32 && y >= 0 && y < self.height as i32
-    }
-
-    fn open_square(&self, (x, y): (i32, i32)) -> bool {
-        // checks if square is in range and free. Safe alternative to square_filled
-
-        // self.in_range((x, y)) && !self.get_square(x as usize, y as usize) // TODO: choose (x,y) vs x,y in function signatures
-        if !self.in_range((x, y)) {
-            return false;
-        }
-        !self.square_filled(x as usize, y as usize)
     }
+}
 
-    fn rotate_piece(&mut self, clockwise: bool) {
-        let jumps = self.active_piece.jump_table(clockwise);
-        let mut rotated_piece = self.active_piece.clone();
-        rotated_piece.rotate(clockwise);
+struct TouchData {
+    start_location: (i32, i32),
+    last_log_location: (i32, i32),
+    start_time: f64,
+    has_moved_sideways: bool,
+}
 
-        for jump in jumps {
-            rotated_piece.translate(jump);
-            if rotated_piece
-                .squares()
-                .iter()
-                .all(|&(x, y)| self.open_square((x, y)))
-            {
-                self.active_piece = rotated_piece;
-                return;
-            }
-            rotated_piece.translate((-jump.0, -jump.1)) // TODO: keep this way or do a "squares_after_translate" method?
-                                                        // in which case maybe redo whole method
+impl TouchData {
+    fn new(location: (i32, i32)) -> Self {
+        Self {
+            start_location: location,
+            last_log_location: location,
+            start_time: instant::now(),
+            has_moved_sideways: false,
         }
     }
+}
 
-    fn lock_and_renew_active_piece(&mut self) {
-        // locks previous piece in place and makes a new one
-        if self.done {
-            return;
-        }
-
-        for (x, y) in self.active_piece.squares() {
-            self.set_square(
-                x as usize,
-                y as usize,
-                self.active_piece.piece_type.to_hue(),
-            ); // unchecked i32 to usize, should be okay though
-        }
-        // let new_piece = random_piece_at(self.width / 2, self.height - 2);
-        let old_stored_piece = std::mem::replace(&mut self.stored_piece, random());
-        let new_piece = Piece {
-            position: ((self.width / 2) as i32, (self.height - 2) as i32),
-            piece_type: old_stored_piece,
-            orientation: Orientation::Deg0,
-        };
-        for (x, y) in new_piece.squares() {
-            if self.in_range((x, y)) && self.square_filled(x as usize, y as usize) {
-                // new piece placed onto occupied square
-                self.done = true;
-            }
-        }
-        self.active_piece = new_piece;
-    }
+fn url_search() -> String {
+    gloo_utils::window().location().search().unwrap_or_default()
+}
 
-    fn clear_full_rows(&mut self) {
-        let mut filled_rows = Vec::new();
-        for (row_nr, row) in self.board.iter().enumerate() {
-            if row.iter().all(|x| x.is_some()) {
-                filled_rows.push(row_nr);
-            }
-        }
+fn url_param<T: std::str::FromStr>(name: &str) -> Option<T> {
+    param(&url_search(), name)
+}
 
-        let points = match filled_rows.len() {
-            0 => 0,
-            1 => 100,
-            2 => 300,
-            3 => 500,
-            4 => 800,
-            _ => unreachable!(),
-        }; // points for clearing rows
-        self.score += points;
-        // let (Some(lowest_filled_row), Some(highest_filled_row)) = (filled_rows.iter().min(), filled_rows.iter().max()) else{
-        //     return;
-        // };
-        for row_nr in filled_rows.into_iter().rev() {
-            self.board.remove(row_nr);
-            self.board.push(vec![None; self.width]);
+// the url's rules plus the ones picked in the settings, the url wins if it has the same one
+fn game_rules() -> String {
+    let search = url_search();
+    let mut rules = vec![search.trim_start_matches('?').to_owned()];
+    for name in ["rotation", "randomizer"] {
+        if let Some(value) = settings::load_rule(name) {
+            rules.push(format!("{name}={value}"));
         }
     }
+    rules.retain(|rule| !rule.is_empty());
+    rules.join("&")
 }
 
-impl Display for Board {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let board_string = (0..self.height)
-            .rev()
-            .map(|y| {
-                let mut line = String::new();
-                for x in 0..self.width {
-                    if self.square_filled(x, y) {
-                        line.push('*');
-                    } else if self.active_piece.squares().contains(&(x as i32, y as i32)) {
-                        line.push('+');
-                    } else {
-                        line.push(' ')
-                    }
-                }
-                line
-            })
-            .map(|line| format!("|{line}|"))
-            .collect::<Vec<_>>()
-            .join("\n");
-        let mut top_border = "+".to_owned();
-        top_border.push_str(&"-".repeat(self.width));
-        top_border.push_str("+\n");
-        write!(f, "{}{}\n{}", top_border, board_string, top_border)
-    }
+// a shared ?seed=... is used for every game, otherwise each one is random
+fn new_seed() -> u64 {
+    url_param("seed").unwrap_or_else(rand::random)
 }
 
-#[derive(Clone)]
-struct Piece {
-    // squares: Vec<(usize, usize)>,
-    position: (i32, i32),
-    piece_type: PieceType, // color
-    orientation: Orientation,
+fn new_replay(board: &Board) -> Replay {
+    Replay::new(
+        board.seed(),
+        board.game_mode().name(),
+        &game_rules(),
+        board.handling().clone(),
+    )
 }
 
-impl Piece {
-    fn move_in_direction(&mut self, direction: Direction) {
-        match direction {
-            Direction::Up => self.position.1 += 1,
-            Direction::Down => self.position.1 -= 1,
-            Direction::Left => self.position.0 -= 1,
-            Direction::Right => self.position.0 += 1,
-        };
-    }
-
-    fn translate(&mut self, (dx, dy): (i32, i32)) {
-        self.position = (self.position.0 + dx, self.position.1 + dy);
-    }
-
-    fn rotate(&mut self, clockwise: bool) {
-        self.orientation = if clockwise {
-            self.orientation.rotate_clockwise()
-        } else {
-            self.orientation.rotate_counterclockwise()
-        }
+// same arrows as the touch buttons, a bar for holding the move to the wall
+fn press_symbol(press: &Press) -> &'static str {
+    match press {
+        Press::Das(Action::MoveLeft) => "⇤",
+        Press::Das(Action::MoveRight) => "⇥",
+        Press::Tap(Action::MoveLeft) => "←",
+        Press::Tap(Action::MoveRight) => "→",
+        Press::Tap(Action::RotateCW) => "↻",
+        Press::Tap(Action::RotateCCW) => "↺",
+        Press::Tap(Action::Rotate180) => "⟳",
+        _ => "?",
     }
+}
 
-    fn squares(&self) -> Vec<(i32, i32)> {
-        self.piece_type
-            .to_squares()
-            .iter()
-            .map(|&(dx, dy)| match self.orientation {
-                Orientation::Deg0 => (dx, dy),
-                Orientation::Deg90 => (dy, -dx),
-                Orientation::Deg180 => (-dx, -dy),
-                Orientation::Deg270 => (-dy, dx),
-            })
-            .map(|(dx, dy)| (self.position.0 + dx, self.position.1 + dy))
-            .collect()
-    }
+// resolves with the requestAnimationFrame timestamp, in milliseconds like instant::now
+async fn next_animation_frame() -> f64 {
+    let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+        let _ = gloo_utils::window().request_animation_frame(&resolve);
+    });
+    wasm_bindgen_futures::JsFuture::from(promise)
+        .await
+        .ok()
+        .and_then(|time| time.as_f64())
+        .unwrap_or_else(instant::now)
+}
 
-    fn squares_after_move(&self, direction: Direction) -> Vec<(i32, i32)> {
-        let (dx, dy) = match direction {
-            Direction::Up => (0, 1),
-            Direction::Down => (0, -1),
-            Direction::Left => (-1, 0),
-            Direction::Right => (1, 0),
-        };
-        self.squares()
-            .iter()
-            .map(|&(x, y)| (x + dx, y + dy))
-            .collect()
-    }
+// when the bot is playing it presses a key this often, slow enough to follow
+const BOT_FRAMES_PER_INPUT: u32 = 6;
 
-    fn squares_after_translation_rotation(
-        &self,
-        translation: (i32, i32),
-        clockwise: bool,
-    ) -> Vec<(i32, i32)> {
-        self.piece_type
-            .to_squares()
-            .iter()
-            .map(|&(dx, dy)| if clockwise { (dy, -dx) } else { (-dy, dx) })
-            .map(|(dx, dy)| {
-                (
-                    self.position.0 + translation.0 + dx,
-                    self.position.1 + translation.1 + dy,
-                )
-            })
-            .collect()
-    }
+// pieces locked plus the active and held piece types, tells apart the pieces the hint plans for
+type PieceKey = (u32, PieceType, Option<PieceType>);
 
-    fn jump_table(&self, clockwise: bool) -> [(i32, i32); 5] {
-        use Orientation as Or;
-        use PieceType as PT;
-        match self.piece_type {
-            PT::J | PT::L | PT::T | PT::S | PT::Z => match (&self.orientation, clockwise) {
-                (Or::Deg0, true) => [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)],
-                (Or::Deg90, false) => [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],
-                (Or::Deg90, true) => [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],
-                (Or::Deg180, false) => [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)],
-                (Or::Deg180, true) => [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],
-                (Or::Deg270, false) => [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],
-                (Or::Deg270, true) => [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],
-                (Or::Deg0, false) => [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],
-            },
-
-            PT::I => match (&self.orientation, clockwise) {
-                (Or::Deg0, true) => [(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)],
-                (Or::Deg90, false) => [(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)],
-                (Or::Deg90, true) => [(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)],
-                (Or::Deg180, false) => [(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)],
-                (Or::Deg180, true) => [(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)],
-                (Or::Deg270, false) => [(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)],
-                (Or::Deg270, true) => [(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)],
-                (Or::Deg0, false) => [(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)],
-            },
-
-            PT::O => [(0, 0); 5],
-        }
-    }
+// what the listeners have seen since the last frame
+#[derive(Default)]
+struct InputState {
+    pressed: Vec<Action>,
+    held: Vec<Action>,
 }
 
+// everything the running game needs, cheap to clone into event listeners
 #[derive(Clone)]
-enum Orientation {
-    Deg0,
-    Deg90,
-    Deg180,
-    Deg270,
+struct Game {
+    board: UseRef<Board>,
+    input: UseRef<InputState>,
+    replay: UseRef<Replay>,
+    paused: UseRef<bool>,
 }
 
-impl Orientation {
-    fn rotate_clockwise(&self) -> Self {
-        match self {
-            Self::Deg0 => Self::Deg90,
-            Self::Deg90 => Self::Deg180,
-            Self::Deg180 => Self::Deg270,
-            Self::Deg270 => Self::Deg0,
+impl Game {
+    fn press(&self, action: Action) {
+        if *self.paused.read() || self.board.read().done() {
+            return;
         }
-    }
-
-    fn rotate_counterclockwise(&self) -> Self {
-        match self {
-            Self::Deg0 => Self::Deg270,
-            Self::Deg90 => Self::Deg0,
-            Self::Deg180 => Self::Deg90,
-            Self::Deg270 => Self::Deg180,
+        let mut input = self.input.write_silent();
+        input.pressed.push(action);
+        if action.direction().is_some() && !input.held.contains(&action) {
+            input.held.push(action);
         }
     }
-}
 
-#[derive(Clone)]
-enum PieceType {
-    I,
-    O,
-    T,
-    J,
-    L,
-    S,
-    Z,
-}
-
-impl PieceType {
-    fn to_squares(&self) -> Vec<(i32, i32)> {
-        match self {
-            PieceType::I => vec![(-1, 0), (0, 0), (1, 0), (2, 0)],
-            PieceType::O => vec![(0, 0), (1, 0), (0, -1), (1, -1)],
-            PieceType::T => vec![(0, 0), (1, 0), (-1, 0), (0, 1)],
-            PieceType::J => vec![(0, 0), (-1, 0), (1, 0), (-1, -1)],
-            PieceType::L => vec![(0, 0), (-1, 0), (1, 0), (1, -1)],
-            PieceType::S => vec![(0, 0), (-1, 0), (0, -1), (1, -1)],
-            PieceType::Z => vec![(0, 0), (1, 0), (0, -1), (-1, -1)],
-        }
+    fn release(&self, action: Action) {
+        self.input
+            .write_silent()
+            .held
+            .retain(|held| *held != action);
     }
 
-    fn average_pos(&self) -> (f32, f32) {
-        let mut sum_x = 0.;
-        let mut sum_y = 0.;
-        for (x, y) in self.to_squares() {
-            sum_x += x as f32;
-            sum_y += y as f32;
-        }
-        (sum_x / 4., sum_y / 4.)
+    // a press that's let go straight away, for touch controls
+    fn tap(&self, action: Action) {
+        self.press(action);
+        self.release(action);
     }
 
-    fn to_hue(&self) -> f32 {
-        match self {
-            PieceType::I => 303.,
-            PieceType::O => 59.,
-            PieceType::T => 28.,
-            PieceType::J => 128.,
-            PieceType::L => 245.,
-            PieceType::S => 183.,
-            PieceType::Z => 0.,
-        }
+    fn release_all(&self) {
+        self.input.write_silent().held.clear();
     }
 
-    // fn to_hue(&self) -> f32 {
-    //     match self {
-    //         PieceType::I => 0.,
-    //         PieceType::O => 51.4,
-    //         PieceType::T => 102.9,
-    //         PieceType::J => 154.3,
-    //         PieceType::L => 205.7,
-    //         PieceType::S => 257.1,
-    //         PieceType::Z => 308.7,
-    //     }
-    // }
-}
+    // runs a frame with whatever happened since the last one, every frame ends up in the replay
+    fn step(&self) -> bool {
+        let input = {
+            let mut input = self.input.write_silent();
+            FrameInput {
+                pressed: std::mem::take(&mut input.pressed),
+                held: input.held.clone(),
+            }
+        };
+        let changed = self.board.write_silent().step(&input);
+        self.replay.write_silent().record(input);
+        changed
+    }
 
-impl Distribution<PieceType> for Standard {
-    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> PieceType {
-        let index: u8 = rng.gen_range(0..7);
-        match index {
-            0 => PieceType::I,
-            1 => PieceType::O,
-            2 => PieceType::T,
-            3 => PieceType::J,
-            4 => PieceType::L,
-            5 => PieceType::S,
-            6 => PieceType::Z,
-            _ => unreachable!(),
+    fn toggle_pause(&self) {
+        if self.board.read().done() {
+            return;
         }
+        // keyups while paused would get lost, so let go of everything
+        self.release_all();
+        self.paused.with_mut(|paused| *paused = !*paused);
     }
-}
-
-#[derive(Clone)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
 
-struct TouchData {
-    start_location: (i32, i32),
-    last_log_location: (i32, i32),
-    id: u64,
-    start_time: f64,
-    has_moved_sideways: bool,
-}
-
-impl TouchData {
-    fn new(location: (i32, i32)) -> Self {
-        Self {
-            start_location: location.clone(),
-            last_log_location: location,
-            id: random(),
-            start_time: instant::now(),
-            has_moved_sideways: false,
-        }
+    fn restart(&self) {
+        self.input.set(InputState::default());
+        self.board.with_mut(|board| {
+            board.restart(new_seed());
+            board.set_handling(settings::load_handling()); // may have changed during the last game
+        });
+        self.replay.set(new_replay(&self.board.read()));
+        self.paused.set(false);
     }
 }
 
-#[allow(non_snake_case)]
-fn BoardView(cx: Scope) -> Element {
-    let board = use_ref(cx, || Board::new(10, 20));
+#[component]
+fn BoardView<'a>(
+    cx: Scope<'a>,
+    game_mode: &'static dyn GameMode,
+    on_quit: EventHandler<'a, ()>,
+    on_watch: EventHandler<'a, Rc<Replay>>,
+) -> Element<'a> {
+    let game_mode: &'static dyn GameMode = *game_mode;
+    let board = use_ref(cx, || {
+        let mut board = build_board(&game_rules(), new_seed(), game_mode);
+        board.set_handling(settings::load_handling());
+        board
+    });
     // let width = gloo_utils::window().screen().unwrap().width().unwrap();
     // let width_interval = width / board.read().width as i32 / 3;
     let width_interval = 20;
 
     // let pressed = use_state(cx, || false);
 
+    let bindings = use_ref(cx, settings::load_bindings);
+    let paused = use_ref(cx, || false);
+    let replay = use_ref(cx, || new_replay(&board.read()));
+    let input = use_ref(cx, InputState::default);
+    // only clones, hooks can't be called from inside another hook's setup
+    let game: &Game = cx.use_hook(|| Game {
+        board: board.clone(),
+        input: input.clone(),
+        replay: replay.clone(),
+        paused: paused.clone(),
+    });
+    let high_scores = use_ref(cx, || HighScores::load(&LocalStorage, game_mode));
+    let new_rank: &UseRef<Option<usize>> = use_ref(cx, || None);
+    let bot_playing = use_ref(cx, || false);
+    let show_hint = use_ref(cx, || false);
+    let hint_plan: &UseRef<Option<(PieceKey, Option<Piece>)>> = use_ref(cx, || None);
+    // finesse trainer: where the current piece should go, and how many so far went there
+    let target = use_ref(cx, FinesseTarget::default);
+    let targets_hit = use_ref(cx, || 0);
+
     let keypress_listener_state = use_state(cx, || None); // just to keep it in scope
+    let keyup_listener_state = use_state(cx, || None);
+    let blur_listener_state = use_state(cx, || None);
+    let visibility_listener_state = use_state(cx, || None);
     let touch_move_listener_state = use_state(cx, || None);
     // let mouse_move_listener_state = use_state(cx, || None);
     let touch_start_listener_state = use_state(cx, || None);
@@ -516,70 +353,106 @@ fn BoardView(cx: Scope) -> Element {
     // let last_touch_x = use_state(cx, || None);
     // let last_touch_y = use_state(cx, || None);
 
-    let _speedup: &Coroutine<u64> = use_coroutine(cx, |mut rx| {
-        to_owned![board, active_touch];
-        async move {
-            loop {
-                if let Some(touchdata) = active_touch.read().as_ref() {
-                    if instant::now() - touchdata.start_time > 500. && !touchdata.has_moved_sideways
-                    {
-                        board.with_mut(|x| x.tick());
-                    }
-                }
-                gloo_timers::future::TimeoutFuture::new(70).await; // not very efficient...
-            }
-        }
-    });
-
     // note to self: if you separate the board out into its own component, this one won't refresh so often
     // so event listeners don't have to be kept between renders (i.e. no need for use_on_create and use_states)
     // issue: stuff needs to exist before attaching event listeners
     use_on_create(cx, || {
         to_owned![
             keypress_listener_state,
+            keyup_listener_state,
+            blur_listener_state,
+            visibility_listener_state,
+            game,
+            bindings,
             touch_move_listener_state,
             touch_start_listener_state,
             touch_end_listener_state,
-            board,
             active_touch,
             in_speedup
         ];
         async move {
             let document_event_target: EventTarget = gloo_utils::document().dyn_into().unwrap();
-            let keypress_listener =
-                gloo_events::EventListener::new(&document_event_target, "keydown", {
-                    to_owned![board];
+            // not passive, rebinding a key cancels its default action
+            let keypress_listener = gloo_events::EventListener::new_with_options(
+                &document_event_target,
+                "keydown",
+                gloo_events::EventListenerOptions::enable_prevent_default(),
+                {
+                    to_owned![game, bindings];
                     move |event| {
                         let event = event.dyn_ref::<web_sys::KeyboardEvent>().unwrap();
-                        match event.key().as_str() {
-                            "ArrowLeft" => {
-                                board.with_mut(|x| x.move_piece(Direction::Left));
-                            }
-                            "ArrowRight" => {
-                                board.with_mut(|x| x.move_piece(Direction::Right));
-                            }
-                            "ArrowDown" => {
-                                board.with_mut(|x| x.tick());
-                            } // tick to immediately move to next piece when active piece hits something
-                            "ArrowUp" => {
-                                board.with_mut(|x| x.rotate_piece(true));
-                            }
-                            "s" => {
-                                board.with_mut(|x| x.do_instant_drop());
-                            }
-                            "e" => {
-                                board.with_mut(|x| x.swap_stored());
-                            }
-                            _ => {}
+                        if event.repeat() {
+                            return; // held keys are repeated by the board instead
+                        }
+                        let Ok(code) = Code::from_str(&event.code()) else {
+                            return;
+                        };
+                        if let Some(action) = bindings.read().listening {
+                            // rebinding in the settings, so this key isn't for the game
+                            event.prevent_default();
+                            bindings.with_mut(|bindings| {
+                                bindings.listening = None;
+                                if code != Code::Escape {
+                                    bindings.bind(action, code);
+                                    settings::save_bindings(bindings);
+                                }
+                            });
+                            return;
+                        }
+                        let Some(action) = bindings.read().action(&code) else {
+                            return;
+                        };
+                        match action {
+                            Action::Pause => game.toggle_pause(),
+                            Action::Restart => game.restart(),
+                            _ => game.press(action),
                         }
                     }
-                });
+                },
+            );
             keypress_listener_state.set(Some(keypress_listener));
 
+            let keyup_listener =
+                gloo_events::EventListener::new(&document_event_target, "keyup", {
+                    to_owned![game, bindings];
+                    move |event| {
+                        let event = event.dyn_ref::<web_sys::KeyboardEvent>().unwrap();
+                        let Ok(code) = Code::from_str(&event.code()) else {
+                            return;
+                        };
+                        if let Some(action) = bindings.read().action(&code) {
+                            game.release(action);
+                        }
+                    }
+                });
+            keyup_listener_state.set(Some(keyup_listener));
+
+            // keyups get lost when switching windows, so don't keep sliding
+            let blur_listener = gloo_events::EventListener::new(&gloo_utils::window(), "blur", {
+                to_owned![game];
+                move |_| game.release_all()
+            });
+            blur_listener_state.set(Some(blur_listener));
+
+            // pause by itself when switching tabs, timers get throttled in the background anyway
+            let visibility_listener =
+                gloo_events::EventListener::new(&document_event_target, "visibilitychange", {
+                    to_owned![game];
+                    move |_| {
+                        if gloo_utils::document().hidden() && !*game.paused.read() {
+                            game.toggle_pause();
+                        }
+                    }
+                });
+            visibility_listener_state.set(Some(visibility_listener));
+
             let touch_move_listener =
                 gloo_events::EventListener::new(&document_event_target, "touchmove", {
-                    to_owned![active_touch, board, in_speedup];
+                    to_owned![active_touch, game, in_speedup];
                     move |event| {
+                        if *game.paused.read() {
+                            return;
+                        }
                         let event = event.dyn_ref::<web_sys::TouchEvent>().unwrap();
                         let Some(moving_touch) = event.touches().get(0) else {
                             return;
@@ -593,10 +466,10 @@ fn BoardView(cx: Scope) -> Element {
 
                             if dx > width_interval {
                                 // moved left
-                                board.with_mut(|x| x.move_piece(Direction::Right));
+                                game.tap(Action::MoveRight);
                             } else if -dx > width_interval {
                                 // moved right
-                                board.with_mut(|x| x.move_piece(Direction::Left));
+                                game.tap(Action::MoveLeft);
                             } else {
                                 // touchmove too small, so rejected
                                 return;
@@ -613,7 +486,7 @@ fn BoardView(cx: Scope) -> Element {
 
             let touch_start_listener =
                 gloo_events::EventListener::new(&document_event_target, "touchstart", {
-                    to_owned![active_touch, board];
+                    to_owned![active_touch];
                     move |event| {
                         let event = event.dyn_ref::<web_sys::TouchEvent>().unwrap();
                         let Some(touch) = event.touches().get(0) else {
@@ -622,7 +495,6 @@ fn BoardView(cx: Scope) -> Element {
                         // last_touch_x.set(Some(touch.screen_x()));
                         // last_touch_y.set(Some(touch.screen_y()));
                         let new_touchdata = TouchData::new((touch.screen_x(), touch.screen_y()));
-                        let new_touch_id = new_touchdata.id;
                         active_touch.set(Some(new_touchdata));
                     }
                 });
@@ -645,6 +517,10 @@ fn BoardView(cx: Scope) -> Element {
                         return;
                     }
 
+                    if *game.paused.read() {
+                        return; // e.g. tapping the menu
+                    }
+
                     let event = event.dyn_ref::<web_sys::TouchEvent>().unwrap();
                     let Some(touch_end) = event.changed_touches().get(0) else {
                         return;
@@ -660,14 +536,25 @@ fn BoardView(cx: Scope) -> Element {
 
                     if touch_end_y - touch_start_y > 100 {
                         // swipe down => instant drop
-                        board.with_mut(|x| x.do_instant_drop());
+                        game.tap(Action::HardDrop);
                     } else if (touch_end_y - touch_start_y).abs() // tap => rotate piece
                         + (touch_end_x - touch_start_x).abs()
                         < 1
                         && instant::now() - active_touch_data.start_time < 400.
                     // 400 for safety margin
                     {
-                        board.with_mut(|x| x.rotate_piece(true));
+                        // tap on the left half turns counterclockwise, right half clockwise
+                        let screen_middle = gloo_utils::window()
+                            .inner_width()
+                            .ok()
+                            .and_then(|width| width.as_f64())
+                            .unwrap_or(0.)
+                            / 2.;
+                        if touch_end.client_x() as f64 >= screen_middle {
+                            game.tap(Action::RotateCW);
+                        } else {
+                            game.tap(Action::RotateCCW);
+                        }
                     }
                 });
 
@@ -675,145 +562,494 @@ fn BoardView(cx: Scope) -> Element {
         }
     });
 
-    let _ticker: &Coroutine<()> = use_coroutine(cx, |_rx| {
-        // TODO: consider using Tokio timeout on rx.next() to get ticks & messages
-        to_owned![board];
+    let _frame: &Coroutine<()> = use_coroutine(cx, |_rx| {
+        // runs the game at a fixed FRAMES_PER_SECOND, however often the browser gets round to drawing
+        to_owned![
+            game,
+            board,
+            high_scores,
+            new_rank,
+            active_touch,
+            bot_playing,
+            target,
+            targets_hit
+        ];
         async move {
-            // let interval = gloo_timers::callback::Interval::new(500, move || {
-            //     n_ticks += 1;
-            //     board.with_mut(|b| b.tick());
-            // });
-            // interval.forget();
+            let frame_ms = FRAME_DURATION.as_secs_f64() * 1000.;
+            let mut last_frame = next_animation_frame().await;
+            let mut lag = 0.; // milliseconds the game is behind real time
+            let mut recorded = false; // whether this game's result is saved yet
+            let mut touch_soft_drop = false;
+            let bot = Bot::default();
+            let mut bot_inputs: Vec<Action> = Vec::new(); // rest of the current plan, backwards
+            let mut bot_piece = 0; // pieces_locked when the plan was made
+            let mut bot_wait = 0;
+            let mut bot_played = false; // games the bot had a go at don't make the high scores
             loop {
-                gloo_timers::future::TimeoutFuture::new(1_000).await;
-                log::info!("tick");
-                board.with_mut(|b| b.tick());
-                if board.read().done {
-                    break;
+                let now = next_animation_frame().await;
+                lag += now - last_frame;
+                last_frame = now;
+                if board.read().done() {
+                    bot_inputs.clear();
+                    if !recorded {
+                        let rank = if game_mode.is_ranked(&board.read()) && !bot_played {
+                            let high_score =
+                                HighScore::from_board(&board.read(), high_scores::now());
+                            let rank = high_scores.write().add(high_score);
+                            if let Err(error) = high_scores.read().save(&mut LocalStorage) {
+                                // losing scores isn't worth stopping the game over
+                                log::warn!("couldn't save high scores: {error}");
+                            }
+                            rank
+                        } else {
+                            None
+                        };
+                        new_rank.set(rank);
+                        recorded = true;
+                        bot_played = false;
+                    }
+                    lag = 0.;
+                    continue;
+                }
+                recorded = false; // restarted
+                if *game.paused.read() {
+                    lag = 0.; // the lock delay doesn't run out while paused
+                    continue;
+                }
+
+                // holding a touch without moving sideways soft drops
+                let touch_held = active_touch.read().as_ref().is_some_and(|touchdata| {
+                    instant::now() - touchdata.start_time > 500. && !touchdata.has_moved_sideways
+                });
+                if touch_held != touch_soft_drop {
+                    if touch_held {
+                        game.press(Action::SoftDrop);
+                    } else {
+                        game.release(Action::SoftDrop);
+                    }
+                    touch_soft_drop = touch_held;
+                }
+
+                let second_before = board.read().play_time().as_secs();
+                let mut changed = false;
+                let mut frames = 0;
+                while lag >= frame_ms {
+                    if *bot_playing.read() {
+                        bot_played = true;
+                        if bot_wait == 0 {
+                            // gravity or the lock delay can get there first, then the rest of the plan is for the wrong piece
+                            if bot_piece != board.read().pieces_locked() {
+                                bot_inputs.clear();
+                            }
+                            if bot_inputs.is_empty() {
+                                // planned from scratch every piece, so the player can take over any time
+                                if let Some(plan) = bot.plan(&board.read()) {
+                                    bot_inputs = plan.inputs.into_iter().rev().collect();
+                                    bot_piece = board.read().pieces_locked();
+                                }
+                            }
+                            if let Some(action) = bot_inputs.pop() {
+                                game.tap(action);
+                            }
+                            bot_wait = BOT_FRAMES_PER_INPUT;
+                        }
+                        bot_wait -= 1;
+                    } else {
+                        bot_inputs.clear();
+                    }
+                    changed |= game.step();
+                    lag -= frame_ms;
+                    frames += 1;
+                    if frames == MAX_CATCH_UP_FRAMES {
+                        lag = 0.;
+                    }
+                }
+                if game_mode.clears_every_piece() {
+                    let board = board.read();
+                    if target.read().hit(&board) {
+                        targets_hit.with_mut(|hit| *hit += 1);
+                    }
+                    // a new one after every lock and hold, silent as it's drawn with the board
+                    let picked = target
+                        .write_silent()
+                        .update(&board, &mut rand::thread_rng());
+                    if picked && board.pieces_locked() == 0 {
+                        targets_hit.set(0); // restarted
+                    }
+                }
+                // the clock on screen only shows whole seconds, no need to redraw more often for that
+                if changed || board.read().play_time().as_secs() != second_before {
+                    board.needs_update();
                 }
             }
         }
     });
 
+    let hint = if game_mode.clears_every_piece() {
+        target.read().piece.clone()
+    } else if *show_hint.read() {
+        let board = board.read();
+        let piece = (
+            board.pieces_locked(),
+            board.active_piece().piece_type.clone(),
+            board.held_piece().cloned(),
+        );
+        // planned once per piece like the bot does, not on every redraw while it moves
+        if hint_plan.read().as_ref().map(|(planned, _)| planned) != Some(&piece) {
+            let plan = Bot::default().plan(&board).map(|plan| plan.piece);
+            *hint_plan.write_silent() = Some((piece, plan));
+        }
+        hint_plan.read().as_ref().and_then(|(_, plan)| plan.clone())
+    } else {
+        None
+    };
+    let stats = board.read().stats().clone();
+    // the last piece's keys when there were too many, with what would have done it
+    let finesse_fault = board
+        .read()
+        .last_finesse()
+        .filter(|finesse| finesse.faults() > 0)
+        .map(|finesse| {
+            let optimal: String = finesse
+                .optimal
+                .iter()
+                .map(press_symbol)
+                .chain(["⭳"])
+                .collect();
+            (finesse.keys.len(), optimal)
+        });
+
     render! {
-        p{ "{board.read().score}"}
+        p{
+            "{board.read().score()}",
+            span { class: "level", "level {board.read().level()}" }
+            button {
+                class: "clearbutton pausebutton",
+                onclick: move |_| game.toggle_pause(),
+                if *paused.read() {"▶"} else {"⏸"}
+            }
+        }
+        p{
+            class: "goal",
+            match game_mode.goal() {
+                Goal::Lines(lines) => rsx!{ "lines {board.read().lines_cleared()}/{lines} · {format_seconds(board.read().play_time())}" },
+                Goal::Time(time) => rsx!{ "time left {format_seconds(time.saturating_sub(board.read().play_time()))}" },
+                Goal::None if game_mode.clears_every_piece() => rsx!{ "targets {targets_hit.read()}/{board.read().pieces_locked()}" },
+                Goal::None => rsx!{ "lines {board.read().lines_cleared()}" },
+            }
+        }
+        p{
+            class: "goal",
+            "finesse faults {board.read().finesse_faults()}"
+            if let Some((keys, optimal)) = &finesse_fault {
+                rsx!{ span { " · {keys} keys, {optimal} would do" } }
+            }
+        }
+        // share the url with ?seed=... to replay the same pieces
+        p{ class: "seed", "seed {board.read().seed()}"}
 
 
 
-        if board.read().done {
-            rsx!{ div {class:"gameover", "Game over"}}
+        // flashes e.g. "T-SPIN DOUBLE" after the piece that did it, keyed so the animation restarts every time
+        for clear in board.read().last_clear().filter(|clear| clear.piece_number == board.read().pieces_locked()).cloned() {
+            div {
+                key: "{clear.piece_number}",
+                class: "clearlabel",
+                if clear.back_to_back {
+                    rsx!{ span { "BACK-TO-BACK " } }
+                }
+                "{clear.name()}"
+                if clear.combo > 0 {
+                    rsx!{ span { " COMBO {clear.combo}" } }
+                }
+            }
+        }
+
+        if board.read().done() {
+            rsx!{
+                div {
+                    class:"gameover",
+                    match (board.read().goal_reached(), game_mode.goal()) {
+                        (false, _) => "Game over",
+                        (true, Goal::Time(_)) => "Time's up!",
+                        (true, _) => "Complete!",
+                    }
+                }
+                // the number the mode is ranked by goes first
+                p {
+                    class: "results",
+                    match game_mode.ranking() {
+                        Ranking::Time if board.read().goal_reached() => rsx!{
+                            span { class: "mainresult", "{format_time(board.read().play_time())}" }
+                            " · {board.read().score()} points"
+                        },
+                        _ => rsx!{
+                            span { class: "mainresult", "{board.read().score()}" }
+                            " points · {format_time(board.read().play_time())}"
+                        },
+                    }
+                    " · {board.read().lines_cleared()} lines · level {board.read().level()}"
+                }
+                p {
+                    class: "results",
+                    "{stats.pieces} pieces · {stats.pps():.2} PPS · {stats.apm():.1} APM · {stats.kpp():.2} KPP"
+                }
+                HighScoreTable { high_scores: high_scores.clone(), new_rank: *new_rank.read() }
+                button { onclick: move |_| game.restart(), "play again" }
+                button { onclick: move |_| on_quit.call(()), "change mode" }
+                button { onclick: move |_| on_watch.call(Rc::new(replay.read().clone())), "watch replay" }
+                a {
+                    class: "download",
+                    href: "data:text/plain;charset=utf-8,{js_sys::encode_uri_component(&replay.read().to_string())}",
+                    download: "tetris-{game_mode.name().to_lowercase()}-{board.read().seed()}.replay",
+                    "save replay"
+                }
+            }
         }
 
 
 
         br {}
 
-        // shows stored piece
+        // shows held piece
         button {
-            class: "clearbutton",
-            onclick: move |_| {board.with_mut(|x| x.swap_stored());},
-            svg {
-                width: 60,
-                height: 60,
-                view_box: "-30 -30 210 210",
-                for (x,y) in board.read().stored_piece.to_squares().into_iter(){
-
-                    Block {
-                        x: ((x as f32 - board.read().stored_piece.average_pos().0 + 1.5) * 40.) as i32 , // x * 40
-                        y: 120 - ((y as f32 - board.read().stored_piece.average_pos().1 + 1.5) * 40.) as i32,  //120 - y * 40
-                        hue: board.read().stored_piece.to_hue(),
-                        opacity: 100.
-                    }
-                }
-                rect {
-                    x: -20,
-                    y: -20,
-                    width: 200,
-                    height: 200,
-                    stroke_width: 10,
-                    stroke: "var(--purple)",
-                    fill: "transparent"
-                }
+            // greyed out while holding isn't allowed
+            class: if board.read().can_hold() {"clearbutton"} else {"clearbutton holdlocked"},
+            onclick: move |_| game.tap(Action::Hold),
+            PiecePreview {
+                piece_type: board.read().held_piece().cloned(),
+                rotation_system: board.read().rotation_system()
             }
         }
 
 
-        // main board
-        svg {
-            width: 200,
-            height: 400,
-            view_box: "-10 -10 410 810",
-            for x in 0..board.read().width {
-                for y in 0..board.read().height {
-                    if let Some(hue) =  board.read().get_square_hue(x,y) {
-                        rsx!{Block {
-                            x: x as i32  *40,
-                            y: 760-(y as i32 *40),
-                            hue: hue,
-                            opacity: 100.
-                        }}
+        Playfield {
+            board: board.clone(),
+            hint: hint,
+            // in-game menu, covers the board so you can't plan ahead while paused
+            if *paused.read() {
+                rsx!{
+                    div {
+                        class: "menu",
+                        p { "Paused" }
+                        button { onclick: move |_| game.toggle_pause(), "resume" }
+                        button { onclick: move |_| game.restart(), "restart" }
+                        button { onclick: move |_| on_quit.call(()), "change mode" }
                     }
                 }
-            },
+            }
+        }
 
-            if !board.read().done {
-                rsx!{
-                    // render instant drop piece
-                    for &(x,y) in board.read().instant_drop_piece().squares().iter() {
 
-                        Block {
-                            x: x * 40,
-                            y: 760 - y * 40,
-                            hue: board.read().active_piece.piece_type.to_hue(),
-                            opacity: 30.
+        // buttons for touch controls, gone while paused so they can't move the piece
+        div {
+            class: "buttons",
+            visibility: if *paused.read() {"hidden"} else {""},
+            button { onclick: move |_| game.tap(Action::MoveLeft), "←"},
+            button { onclick: move |_| game.tap(Action::MoveRight), "→"}
+            button { onclick: move |_| game.tap(Action::HardDrop), "⭳"}// used to be x.tick()
+
+            button { onclick: move |_| game.tap(Action::RotateCCW), "↺"}
+            button { onclick: move |_| game.tap(Action::RotateCW), "↻"}
+            button { onclick: move |_| game.tap(Action::Rotate180), "⟳"}
+            button { onclick: move |_| game.tap(Action::Hold), "🗘"}  // ⤮⮂🗘⮁
+        }
+
+        // the bot can play by itself or just show where it would put the piece
+        div {
+            class: "botcontrols",
+            button {
+                onclick: move |_| bot_playing.with_mut(|playing| *playing = !*playing),
+                if *bot_playing.read() {"stop AI"} else {"watch AI play"}
+            }
+            button {
+                onclick: move |_| show_hint.with_mut(|show| *show = !*show),
+                if *show_hint.read() {"hide hint"} else {"show hint"}
+            }
+        }
+
+        HandlingSettings {}
+        KeyBindingSettings { bindings: bindings.clone() }
+
+    }
+}
+
+// the board with the falling piece and the next queue, children go on top of the board (e.g. menus)
+// a hint is drawn see-through like the ghost, wherever the bot would put a piece
+#[component]
+fn Playfield<'a>(
+    cx: Scope<'a>,
+    board: UseRef<Board>,
+    #[props(!optional)] hint: Option<Piece>,
+    children: Element<'a>,
+) -> Element<'a> {
+    render! {
+        div {
+            class: "playfield",
+            // main board
+            svg {
+                width: 200,
+                height: 400,
+                view_box: "-10 -10 410 810",
+                for x in 0..board.read().width() {
+                    for y in 0..board.read().height() {
+                        if let Some(hue) =  board.read().get_square_hue(x,y) {
+                            rsx!{Block {
+                                x: x as i32  *40,
+                                y: 760-(y as i32 *40),
+                                hue: hue,
+                                opacity: 100.
+                            }}
                         }
                     }
+                },
+
+                if !board.read().done() {
+                    rsx!{
+                        // render instant drop piece
+                        for &(x,y) in board.read().instant_drop_piece().squares().iter() {
+
+                            Block {
+                                x: x * 40,
+                                y: 760 - y * 40,
+                                hue: board.read().active_piece().piece_type.to_hue(),
+                                opacity: 30.
+                            }
+                        }
 
-                    // render active piece
-                    for &(x,y) in board.read().active_piece.squares().iter() {
+                        for hint in hint.iter() {
+                            for &(x,y) in hint.squares().iter() {
+                                Block {
+                                    x: x * 40,
+                                    y: 760 - y * 40,
+                                    hue: hint.piece_type.to_hue(),
+                                    opacity: 30.
+                                }
+                            }
+                        }
 
-                        Block {
-                            x: x * 40,
-                            y: 760 - y * 40,
-                            hue: board.read().active_piece.piece_type.to_hue(),
-                            opacity: 100.
+                        // render active piece
+                        for &(x,y) in board.read().active_piece().squares().iter() {
+
+                            Block {
+                                x: x * 40,
+                                y: 760 - y * 40,
+                                hue: board.read().active_piece().piece_type.to_hue(),
+                                opacity: 100.
+                            }
                         }
-                    }
 
 
+                    }
                 }
-            }
 
-            rect { // border around game
-                x: 0,
-                y: 0,
-                width: 400,
-                height:800,
-                stroke_width: 5,
-                stroke: "var(--purple)",
-                fill: "transparent"
+                rect { // border around game
+                    x: 0,
+                    y: 0,
+                    width: 400,
+                    height:800,
+                    stroke_width: 5,
+                    stroke: "var(--purple)",
+                    fill: "transparent"
+                }
+
+
             }
 
+            children
 
-        }
+            // next pieces, first one on top
+            div {
+                class: "nextqueue",
+                for piece_type in board.read().next_pieces().cloned() {
+                    PiecePreview { piece_type: Some(piece_type), rotation_system: board.read().rotation_system() }
+                }
+            }
 
+            StatsPanel { board: board.clone() }
+        }
+    }
+}
 
-        // buttons for touch controls
+// live numbers for the game so far, rates first
+#[component]
+fn StatsPanel(cx: Scope, board: UseRef<Board>) -> Element<'a> {
+    let stats = board.read().stats().clone();
+    let clears = [
+        ("singles", stats.singles),
+        ("doubles", stats.doubles),
+        ("triples", stats.triples),
+        ("tetrises", stats.tetrises),
+    ];
+    let [t_spin_zeros, t_spin_singles, t_spin_doubles, t_spin_triples] = stats.t_spins;
+    render! {
         div {
-            class: "buttons",
-            button { onclick: |_| {board.with_mut(|x| x.move_piece(Direction::Left));}, "←"},
-            button { onclick: |_| {board.with_mut(|x| x.move_piece(Direction::Right));}, "→"}
-            button { onclick: |_| {board.with_mut(|x| x.do_instant_drop());}, "⭳"}// used to be x.tick()
-
-            button { onclick: |_| {board.with_mut(|x| x.rotate_piece(true));}, "↻"}
-            button { onclick: |_| {board.with_mut(|x| x.swap_stored());}, "🗘"}  // ⤮⮂🗘⮁
+            class: "stats",
+            p { "{stats.pps():.2} PPS" }
+            p { "{stats.apm():.1} APM" }
+            p { "{stats.kpp():.2} KPP" }
+            p { "{stats.pieces} pieces · {format_seconds(stats.time)}" }
+            p { "{stats.keys} keys · {stats.holds} holds" }
+            for (name, count) in clears {
+                p { "{count} {name}" }
+            }
+            // no lines, single, double, triple
+            p { "T-spins {t_spin_zeros}/{t_spin_singles}/{t_spin_doubles}/{t_spin_triples}" }
+            p {
+                for piece_type in PieceType::ALL {
+                    span {
+                        style: "color: hsl({piece_type.to_hue()}, 100%, 50%)",
+                        "{piece_type.letter()}{stats.pieces_of(&piece_type)} "
+                    }
+                }
+            }
         }
+    }
+}
+
+#[component]
+fn PiecePreview(
+    cx: Scope,
+    #[props(!optional)] piece_type: Option<PieceType>,
+    rotation_system: &'static dyn RotationSystem,
+) -> Element<'a> {
+    render! {
+        svg {
+            width: 60,
+            height: 60,
+            view_box: "-30 -30 210 210",
+            if let Some(piece_type) = piece_type {
+                // spawn orientation of this rotation system, centred in the box
+                let squares = rotation_system.shape(piece_type, &Orientation::Deg0);
+                let avg_x = squares.iter().map(|&(x, _)| x as f32).sum::<f32>() / 4.;
+                let avg_y = squares.iter().map(|&(_, y)| y as f32).sum::<f32>() / 4.;
+                rsx!{
+                    for (x,y) in squares.into_iter(){
 
+                        Block {
+                            x: ((x as f32 - avg_x + 1.5) * 40.) as i32 , // x * 40
+                            y: 120 - ((y as f32 - avg_y + 1.5) * 40.) as i32,  //120 - y * 40
+                            hue: piece_type.to_hue(),
+                            opacity: 100.
+                        }
+                    }
+                }
+            }
+            rect {
+                x: -20,
+                y: -20,
+                width: 200,
+                height: 200,
+                stroke_width: 10,
+                stroke: "var(--purple)",
+                fill: "transparent"
+            }
+        }
     }
 }
 
 #[component]
-fn Block(cx: Scope, x: i32, y: i32, hue: f32, opacity: f32) -> Element {
+fn Block(cx: Scope, x: i32, y: i32, hue: f32, opacity: f32) -> Element<'a> {
     render! {
         g {
             transform:"
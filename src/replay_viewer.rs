@@ -0,0 +1,143 @@
+// watches a recorded game by running its events through a fresh board
+use std::{rc::Rc, time::Duration};
+
+use dioxus::prelude::*;
+use tetris_core::{build_board, format_time, Board, Endless, Replay, FRAME_DURATION, GAME_MODES};
+
+use crate::{next_animation_frame, PiecePreview, Playfield};
+
+const SPEEDS: [f64; 5] = [0.25, 0.5, 1., 2., 4.];
+
+struct Playback {
+    replay: Rc<Replay>,
+    position: usize, // number of frames played so far
+    clock: Duration,
+}
+
+impl Playback {
+    fn finished(&self) -> bool {
+        self.position == self.replay.frames.len()
+    }
+
+    // boards can't go back in time, so seeking backwards plays everything again from the start
+    fn seek(&mut self, board: &mut Board, to: Duration) {
+        let target = (to.as_nanos() / FRAME_DURATION.as_nanos()) as usize;
+        if target < self.position {
+            board.restart(self.replay.seed);
+            self.position = 0;
+        }
+        while !self.finished() && self.position < target {
+            board.step(&self.replay.frames[self.position]);
+            self.position += 1;
+        }
+        self.clock = to;
+    }
+}
+
+#[component]
+pub fn ReplayViewer<'a>(
+    cx: Scope<'a>,
+    replay: Rc<Replay>,
+    on_quit: EventHandler<'a, ()>,
+) -> Element<'a> {
+    let board = use_ref(cx, || {
+        let game_mode = GAME_MODES
+            .into_iter()
+            .find(|mode| mode.name() == replay.game_mode)
+            .unwrap_or(&Endless);
+        let mut board = build_board(&replay.rules, replay.seed, game_mode);
+        board.set_handling(replay.handling.clone());
+        board
+    });
+    let playback = use_ref(cx, || Playback {
+        replay: replay.clone(),
+        position: 0,
+        clock: Duration::ZERO,
+    });
+    let playing = use_ref(cx, || true);
+    let speed = use_ref(cx, || 1.);
+    let duration = replay.duration();
+
+    let _player: &Coroutine<()> = use_coroutine(cx, |_rx| {
+        to_owned![board, playback, playing, speed];
+        async move {
+            let mut last_frame = next_animation_frame().await;
+            loop {
+                let now = next_animation_frame().await;
+                let elapsed = Duration::from_secs_f64((now - last_frame) / 1000. * *speed.read());
+                last_frame = now;
+                if !*playing.read() {
+                    continue;
+                }
+                let to = playback.read().clock + elapsed;
+                playback.write().seek(&mut board.write(), to);
+                if playback.read().finished() {
+                    playing.set(false);
+                }
+            }
+        }
+    });
+
+    let seek = move |to: Duration| playback.write().seek(&mut board.write(), to);
+
+    render! {
+        p {
+            "{board.read().score()}",
+            span { class: "level", "level {board.read().level()}" }
+        }
+        p {
+            class: "goal",
+            "{replay.game_mode} · lines {board.read().lines_cleared()} · {format_time(board.read().play_time())}"
+        }
+        p { class: "seed", "replay of seed {replay.seed}" }
+
+        br {}
+
+        PiecePreview {
+            piece_type: board.read().held_piece().cloned(),
+            rotation_system: board.read().rotation_system()
+        }
+
+        Playfield { board: board.clone(), hint: None }
+
+        div {
+            class: "replaycontrols",
+            button {
+                onclick: move |_| {
+                    if playback.read().finished() {
+                        seek(Duration::ZERO); // play again from the start
+                    }
+                    playing.with_mut(|playing| *playing = !*playing);
+                },
+                if *playing.read() {"⏸"} else {"▶"}
+            }
+            input {
+                r#type: "range",
+                min: 0,
+                max: "{duration.as_millis()}",
+                value: "{playback.read().clock.as_millis()}",
+                oninput: move |event| {
+                    if let Ok(millis) = event.value.parse() {
+                        seek(Duration::from_millis(millis));
+                    }
+                }
+            }
+            "{format_time(playback.read().clock)} / {format_time(duration)}"
+            select {
+                onchange: move |event| {
+                    if let Ok(new_speed) = event.value.parse() {
+                        speed.set(new_speed);
+                    }
+                },
+                for option_speed in SPEEDS {
+                    option {
+                        value: "{option_speed}",
+                        selected: option_speed == *speed.read(),
+                        "{option_speed}×"
+                    }
+                }
+            }
+            button { onclick: move |_| on_quit.call(()), "back" }
+        }
+    }
+}
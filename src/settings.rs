@@ -0,0 +1,284 @@
+// player settings that are kept in localStorage between visits
+use std::{collections::HashMap, str::FromStr, time::Duration};
+
+use dioxus::html::input_data::keyboard_types::Code;
+use dioxus::prelude::*;
+use tetris_core::{Action, Handling, RotationSystem, Srs, ROTATION_SYSTEMS};
+
+const HANDLING_KEY: &str = "handling";
+const BINDINGS_KEY: &str = "bindings";
+
+pub fn local_storage() -> Option<web_sys::Storage> {
+    gloo_utils::window().local_storage().ok().flatten()
+}
+
+// stored as "das_ms,arr_ms,soft_drop_factor"
+pub fn load_handling() -> Handling {
+    let Some(stored) =
+        local_storage().and_then(|storage| storage.get_item(HANDLING_KEY).ok().flatten())
+    else {
+        return Handling::default();
+    };
+    let values: Vec<u64> = stored
+        .split(',')
+        .filter_map(|value| value.parse().ok())
+        .collect();
+    let [das, arr, soft_drop_factor] = values[..] else {
+        return Handling::default(); // corrupted or from an older version
+    };
+    Handling {
+        das: Duration::from_millis(das),
+        arr: Duration::from_millis(arr),
+        soft_drop_factor: soft_drop_factor as u32,
+    }
+}
+
+pub fn save_handling(handling: &Handling) {
+    let value = format!(
+        "{},{},{}",
+        handling.das.as_millis(),
+        handling.arr.as_millis(),
+        handling.soft_drop_factor
+    );
+    if let Some(storage) = local_storage() {
+        let _ = storage.set_item(HANDLING_KEY, &value); // not being able to save isn't worth crashing over
+    }
+}
+
+// only saved here, the game picks the new handling up when it restarts. Changing it halfway would
+// make the game's replay play back differently
+#[component]
+pub fn HandlingSettings(cx: Scope) -> Element {
+    let handling = use_ref(cx, load_handling);
+    let update = move |change: &dyn Fn(&mut Handling, u64), value: String| {
+        let Ok(value) = value.parse() else {
+            return;
+        };
+        handling.with_mut(|handling| {
+            change(handling, value);
+            save_handling(handling);
+        });
+    };
+    let handling = handling.read().clone();
+    render! {
+        details {
+            class: "settings",
+            summary { "handling" }
+            p { "changes take effect from the next game" }
+            label {
+                "DAS (ms) "
+                input {
+                    r#type: "number",
+                    min: 0,
+                    value: "{handling.das.as_millis()}",
+                    oninput: move |event| update(&|handling, value| handling.das = Duration::from_millis(value), event.value.clone())
+                }
+            }
+            label {
+                "ARR (ms) "
+                input {
+                    r#type: "number",
+                    min: 0,
+                    value: "{handling.arr.as_millis()}",
+                    oninput: move |event| update(&|handling, value| handling.arr = Duration::from_millis(value), event.value.clone())
+                }
+            }
+            label {
+                "soft drop factor "
+                input {
+                    r#type: "number",
+                    min: 1,
+                    value: "{handling.soft_drop_factor}",
+                    oninput: move |event| update(&|handling, value| handling.soft_drop_factor = value as u32, event.value.clone())
+                }
+            }
+        }
+    }
+}
+
+// rules picked in the settings, stored under the rule's own name. None until the player picks one
+pub fn load_rule(name: &str) -> Option<String> {
+    local_storage().and_then(|storage| storage.get_item(name).ok().flatten())
+}
+
+pub fn save_rule(name: &str, value: &str) {
+    if let Some(storage) = local_storage() {
+        let _ = storage.set_item(name, value);
+    }
+}
+
+// picked before a game starts, they're part of the rules the board is built from and the replay keeps
+#[component]
+pub fn RuleSettings(cx: Scope) -> Element {
+    let rotation = use_state(cx, || {
+        load_rule("rotation").unwrap_or_else(|| Srs.name().to_owned())
+    });
+    let randomizer = use_state(cx, || load_rule("randomizer").unwrap_or("bag".to_owned()));
+    render! {
+        details {
+            class: "settings",
+            summary { "rules" }
+            label {
+                "rotation system "
+                select {
+                    onchange: move |event| {
+                        save_rule("rotation", &event.value);
+                        rotation.set(event.value.clone());
+                    },
+                    for system in ROTATION_SYSTEMS {
+                        option {
+                            value: "{system.name()}",
+                            selected: system.name().eq_ignore_ascii_case(rotation.get()),
+                            "{system.name()}"
+                        }
+                    }
+                }
+            }
+            label {
+                "randomizer "
+                select {
+                    onchange: move |event| {
+                        save_rule("randomizer", &event.value);
+                        randomizer.set(event.value.clone());
+                    },
+                    option { value: "bag", selected: randomizer.get() != "uniform", "7-bag" }
+                    option { value: "uniform", selected: randomizer.get() == "uniform", "uniform" }
+                }
+            }
+        }
+    }
+}
+
+// keys are matched on KeyboardEvent.code, i.e. their position, so the layout doesn't matter
+pub struct KeyBindings {
+    keys: HashMap<Action, Vec<Code>>,
+    pub listening: Option<Action>, // the next key pressed gets bound to this
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        let keys = HashMap::from([
+            (Action::MoveLeft, vec![Code::ArrowLeft]),
+            (Action::MoveRight, vec![Code::ArrowRight]),
+            (Action::SoftDrop, vec![Code::ArrowDown]),
+            (Action::HardDrop, vec![Code::KeyS, Code::Space]),
+            (Action::RotateCW, vec![Code::ArrowUp, Code::KeyX]),
+            (Action::RotateCCW, vec![Code::KeyZ, Code::ControlLeft]),
+            (Action::Rotate180, vec![Code::KeyA]),
+            (Action::Hold, vec![Code::KeyE, Code::KeyC]),
+            (Action::Pause, vec![Code::Escape, Code::KeyP]),
+            (Action::Restart, vec![Code::KeyR]),
+        ]);
+        Self {
+            keys,
+            listening: None,
+        }
+    }
+}
+
+impl KeyBindings {
+    pub fn keys(&self, action: &Action) -> &[Code] {
+        self.keys.get(action).map_or(&[], |keys| keys.as_slice())
+    }
+
+    pub fn action(&self, code: &Code) -> Option<Action> {
+        Action::ALL
+            .into_iter()
+            .find(|action| self.keys(action).contains(code))
+    }
+
+    // a key can only do one thing, so it's taken away from whatever it was bound to before
+    pub fn bind(&mut self, action: Action, code: Code) {
+        for keys in self.keys.values_mut() {
+            keys.retain(|key| *key != code);
+        }
+        self.keys.entry(action).or_default().push(code);
+    }
+
+    pub fn unbind(&mut self, action: &Action, code: &Code) {
+        if let Some(keys) = self.keys.get_mut(action) {
+            keys.retain(|key| key != code);
+        }
+    }
+}
+
+// stored as one "Action=Code,Code" line per action
+pub fn load_bindings() -> KeyBindings {
+    let mut bindings = KeyBindings::default();
+    let Some(stored) =
+        local_storage().and_then(|storage| storage.get_item(BINDINGS_KEY).ok().flatten())
+    else {
+        return bindings;
+    };
+    for (name, codes) in stored.lines().filter_map(|line| line.split_once('=')) {
+        // actions added in later versions keep their defaults
+        let Some(action) = Action::ALL
+            .into_iter()
+            .find(|action| format!("{action:?}") == name)
+        else {
+            continue;
+        };
+        let codes = codes
+            .split(',')
+            .filter_map(|code| Code::from_str(code).ok())
+            .collect();
+        bindings.keys.insert(action, codes);
+    }
+    bindings
+}
+
+pub fn save_bindings(bindings: &KeyBindings) {
+    let value = Action::ALL
+        .iter()
+        .map(|action| {
+            let codes: Vec<String> = bindings.keys(action).iter().map(Code::to_string).collect();
+            format!("{action:?}={}", codes.join(","))
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    if let Some(storage) = local_storage() {
+        let _ = storage.set_item(BINDINGS_KEY, &value);
+    }
+}
+
+#[component]
+pub fn KeyBindingSettings(cx: Scope, bindings: UseRef<KeyBindings>) -> Element<'a> {
+    render! {
+        details {
+            class: "settings",
+            summary { "controls" }
+            table {
+                for action in Action::ALL {
+                    tr {
+                        td { "{action.name()}" }
+                        td {
+                            for code in bindings.read().keys(&action).iter().cloned() {
+                                button {
+                                    class: "keybinding",
+                                    title: "remove",
+                                    onclick: move |_| bindings.with_mut(|bindings| {
+                                        bindings.unbind(&action, &code);
+                                        save_bindings(bindings);
+                                    }),
+                                    "{code}"
+                                }
+                            }
+                            button {
+                                class: "keybinding",
+                                onclick: move |_| bindings.with_mut(|bindings| bindings.listening = Some(action)),
+                                if bindings.read().listening == Some(action) { "press a key…" } else { "+" }
+                            }
+                        }
+                    }
+                }
+            }
+            button {
+                onclick: move |_| bindings.with_mut(|bindings| {
+                    *bindings = KeyBindings::default();
+                    save_bindings(bindings);
+                }),
+                "reset to defaults"
+            }
+        }
+    }
+}
@@ -0,0 +1,1053 @@
+use std::{collections::VecDeque, fmt::Display, str::FromStr, time::Duration};
+
+use rand::{random, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::finesse::Finesse;
+use crate::game_mode::{Endless, GameMode};
+use crate::gravity::{Gravity, GravityCurve, FRAME_DURATION};
+use crate::input::{Action, AutoShift, FrameInput, Handling};
+use crate::lock_delay::{LockDelay, LockReset};
+use crate::piece::{Direction, Orientation, Piece, PieceType};
+use crate::randomizer::{Randomizer, SevenBag};
+use crate::rotation::{Rotation, RotationSystem, Srs};
+use crate::scoring::{GuidelineScoring, LineClear, Scoring, TSpin};
+use crate::stats::Stats;
+
+pub const MAX_PREVIEW_LENGTH: usize = 6;
+
+// grey-ish blue, not used by any piece
+pub const GARBAGE_HUE: f32 = 220.;
+
+// slack for float error when gravity adds up to a whole cell, so 1/48 G added 48 times drops on
+// the 48th frame and not one late
+const GRAVITY_ROUNDING: f32 = 1e-4;
+
+// what a locked square was, so it keeps its colour
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Square {
+    Piece(PieceType),
+    Garbage, // put there by something other than a piece locking, e.g. a puzzle
+}
+
+impl Square {
+    pub fn to_hue(&self) -> f32 {
+        match self {
+            Square::Piece(piece_type) => piece_type.to_hue(),
+            Square::Garbage => GARBAGE_HUE,
+        }
+    }
+
+    pub fn letter(&self) -> char {
+        match self {
+            Square::Piece(piece_type) => piece_type.letter(),
+            Square::Garbage => 'G',
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HoldRule {
+    Guideline, // held piece comes back at the top, once per piece
+    Swap,      // swap in place as often as you like
+}
+
+pub struct Board {
+    board: Vec<Vec<Option<Square>>>,
+    width: usize,
+    height: usize,
+    active_piece: Piece,
+    next_pieces: VecDeque<PieceType>, // always MAX_PREVIEW_LENGTH long, preview_length decides how many are shown
+    preview_length: usize,
+    held_piece: Option<PieceType>,
+    hold_rule: HoldRule,
+    hold_used: bool, // reset when a piece locks
+    lock_delay: LockDelay,
+    lock_timer: Option<Duration>, // time spent on the ground, None while falling
+    lock_resets: u32,
+    lowest_y: i32, // lowest row the active piece has reached, for step reset and giving back resets
+    done: bool,
+    goal_reached: bool, // done because the game mode's goal was met, not by topping out
+    game_mode: &'static dyn GameMode,
+    score: u32,
+    lines_cleared: u32,
+    scoring: Box<dyn Scoring>,
+    combo: Option<u32>, // None when the last piece didn't clear anything
+    back_to_back: bool, // last line clear was a difficult one
+    last_clear: Option<LineClear>,
+    stats: Stats, // pieces locked and play time are in here too
+    last_rotation: Option<(Rotation, usize)>, // rotation and kick index if the last successful move was a rotation
+    piece_start: Piece,                       // the active piece as it came into play, for finesse
+    piece_keys: Vec<Action>,                  // moves and rotations pressed since then
+    finesse_faults: u32,
+    last_finesse: Option<Finesse>,
+    rotation_system: &'static dyn RotationSystem,
+    start_level: u32,
+    gravity_curve: GravityCurve,
+    gravity_cells: f32, // gravity built up towards the next cell, for step. Fractions carry over
+    auto_shift: AutoShift,
+    randomizer: Box<dyn Randomizer>,
+    seed: u64,
+    rng: ChaCha8Rng, // chacha so the same seed gives the same game on every platform
+}
+
+impl Board {
+    // the constructors fail if the board is too small for every piece to come into play on it
+    pub fn new(width: usize, height: usize) -> Result<Self, String> {
+        Self::with_seed(width, height, random())
+    }
+
+    pub fn with_seed(width: usize, height: usize, seed: u64) -> Result<Self, String> {
+        Self::with_randomizer(width, height, seed, Box::new(SevenBag::new()))
+    }
+
+    pub fn with_randomizer(
+        width: usize,
+        height: usize,
+        seed: u64,
+        randomizer: Box<dyn Randomizer>,
+    ) -> Result<Self, String> {
+        Self::with_rules(width, height, seed, randomizer, &Srs)
+    }
+
+    pub fn with_rules(
+        width: usize,
+        height: usize,
+        seed: u64,
+        randomizer: Box<dyn Randomizer>,
+        rotation_system: &'static dyn RotationSystem,
+    ) -> Result<Self, String> {
+        // placeholder, the game itself is set up by restart
+        let placeholder = Piece {
+            position: (0, 0),
+            piece_type: PieceType::I,
+            orientation: Orientation::Deg0,
+            rotation_system,
+        };
+        let mut board = Board {
+            board: Vec::new(),
+            width,
+            height,
+            active_piece: placeholder.clone(),
+            next_pieces: VecDeque::new(),
+            preview_length: 5,
+            held_piece: None,
+            hold_rule: HoldRule::Guideline,
+            hold_used: false,
+            lock_delay: LockDelay::default(),
+            lock_timer: None,
+            lock_resets: 0,
+            lowest_y: 0,
+            done: false,
+            goal_reached: false,
+            game_mode: &Endless,
+            score: 0,
+            lines_cleared: 0,
+            scoring: Box::new(GuidelineScoring),
+            combo: None,
+            back_to_back: false,
+            last_clear: None,
+            stats: Stats::default(),
+            last_rotation: None,
+            piece_start: placeholder,
+            piece_keys: Vec::new(),
+            finesse_faults: 0,
+            last_finesse: None,
+            rotation_system,
+            start_level: 1,
+            gravity_curve: GravityCurve::Guideline,
+            gravity_cells: 0.,
+            auto_shift: AutoShift::default(),
+            randomizer,
+            seed,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        };
+        let spawns = PieceType::ALL
+            .into_iter()
+            .map(|piece_type| board.new_piece(piece_type));
+        if !spawns
+            .flat_map(|piece| piece.squares())
+            .all(|square| board.in_range(square))
+        {
+            return Err(format!(
+                "a {width}x{height} board is too small for every piece to spawn"
+            ));
+        }
+        board.restart(seed);
+        Ok(board)
+    }
+
+    // starts a new game in place, keeping all the rules and settings
+    pub fn restart(&mut self, seed: u64) {
+        self.seed = seed;
+        self.rng = ChaCha8Rng::seed_from_u64(seed);
+        self.randomizer.reset();
+        let piece_type = self.randomizer.next_piece(&mut self.rng);
+        let mut first_piece = self.new_piece(piece_type.clone());
+        first_piece.position.1 = self.height.saturating_sub(4) as i32;
+        if !first_piece
+            .squares()
+            .into_iter()
+            .all(|square| self.in_range(square))
+        {
+            first_piece = self.new_piece(piece_type); // too low on a short board
+        }
+        let spawn_y = first_piece.position.1;
+        self.active_piece = first_piece;
+        self.next_pieces = (0..MAX_PREVIEW_LENGTH)
+            .map(|_| self.randomizer.next_piece(&mut self.rng))
+            .collect();
+        self.board = vec![vec![None; self.width]; self.height];
+        self.held_piece = None;
+        self.hold_used = false;
+        self.lock_timer = None;
+        self.lock_resets = 0;
+        self.lowest_y = spawn_y;
+        self.done = false;
+        self.goal_reached = false;
+        self.score = 0;
+        self.lines_cleared = 0;
+        self.combo = None;
+        self.back_to_back = false;
+        self.last_clear = None;
+        self.stats = Stats::default();
+        self.last_rotation = None;
+        self.piece_start = self.active_piece.clone();
+        self.piece_keys.clear();
+        self.finesse_faults = 0;
+        self.last_finesse = None;
+        self.gravity_cells = 0.;
+        self.auto_shift = AutoShift::new(self.auto_shift.handling.clone());
+    }
+
+    pub fn set_handling(&mut self, handling: Handling) {
+        self.auto_shift.handling = handling;
+    }
+
+    pub fn handling(&self) -> &Handling {
+        &self.auto_shift.handling
+    }
+
+    // one frame of the game at FRAMES_PER_SECOND: the frame's input first, then auto repeat, gravity
+    // and the lock delay. The same inputs always give the same game, whatever calls this and however
+    // late. Returns whether anything changed
+    pub fn step(&mut self, input: &FrameInput) -> bool {
+        if self.done {
+            return false;
+        }
+        // taken out for a moment because it needs the board to move the piece
+        let mut auto_shift = std::mem::take(&mut self.auto_shift);
+        let mut changed = false;
+        for action in &input.pressed {
+            changed |= self.press(&mut auto_shift, action);
+        }
+        for action in Action::ALL {
+            if let Some(direction) = action.direction() {
+                if !input.held.contains(&action) {
+                    auto_shift.release(direction);
+                }
+            }
+        }
+        changed |= auto_shift.update(self, FRAME_DURATION);
+        self.auto_shift = auto_shift;
+
+        if !self.done {
+            self.gravity_cells += self.gravity().0;
+            while self.gravity_cells >= 1. - GRAVITY_ROUNDING {
+                self.gravity_cells -= 1.;
+                self.tick();
+                changed = true;
+            }
+        }
+        changed |= self.advance(FRAME_DURATION);
+        changed
+    }
+
+    fn press(&mut self, auto_shift: &mut AutoShift, action: &Action) -> bool {
+        if !matches!(action, Action::Pause | Action::Restart) {
+            self.stats.keys += 1;
+        }
+        if matches!(
+            action,
+            Action::MoveLeft
+                | Action::MoveRight
+                | Action::RotateCW
+                | Action::RotateCCW
+                | Action::Rotate180
+        ) {
+            self.piece_keys.push(*action);
+        }
+        if let Some(direction) = action.direction() {
+            auto_shift.press(self, direction);
+            return true;
+        }
+        match action {
+            Action::HardDrop => self.do_instant_drop(),
+            Action::RotateCW => self.rotate_piece(true),
+            Action::RotateCCW => self.rotate_piece(false),
+            Action::Rotate180 => self.rotate_piece_180(),
+            Action::Hold => self.hold_piece(),
+            Action::Pause | Action::Restart => return false, // up to whoever runs the game
+            Action::MoveLeft | Action::MoveRight | Action::SoftDrop => unreachable!(), // have a direction
+        }
+        true
+    }
+
+    fn pop_next_piece(&mut self) -> PieceType {
+        let new_piece_type = self.randomizer.next_piece(&mut self.rng);
+        self.next_pieces.push_back(new_piece_type);
+        self.next_pieces.pop_front().unwrap() // queue is never empty
+    }
+
+    pub fn set_preview_length(&mut self, length: usize) {
+        self.preview_length = length.clamp(1, MAX_PREVIEW_LENGTH);
+    }
+
+    pub fn set_game_mode(&mut self, game_mode: &'static dyn GameMode) {
+        self.game_mode = game_mode;
+    }
+
+    pub fn game_mode(&self) -> &'static dyn GameMode {
+        self.game_mode
+    }
+
+    pub fn goal_reached(&self) -> bool {
+        self.goal_reached
+    }
+
+    // ends the game if the game mode's goal is met, returns whether it did
+    fn check_goal(&mut self) -> bool {
+        if self.game_mode.goal_reached(self) {
+            self.goal_reached = true;
+            self.done = true;
+        }
+        self.goal_reached
+    }
+
+    pub fn rotation_system(&self) -> &'static dyn RotationSystem {
+        self.rotation_system
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn active_piece(&self) -> &Piece {
+        &self.active_piece
+    }
+
+    // puts a piece in play as if it just spawned there, e.g. to set up a position
+    pub fn set_active_piece(&mut self, piece: Piece) {
+        self.lowest_y = piece.position.1;
+        self.last_rotation = None;
+        self.lock_timer = None;
+        self.lock_resets = 0;
+        self.gravity_cells = 0.;
+        self.piece_start = piece.clone();
+        self.piece_keys.clear();
+        self.active_piece = piece;
+    }
+
+    pub fn next_pieces(&self) -> impl Iterator<Item = &PieceType> {
+        self.next_pieces.iter().take(self.preview_length)
+    }
+
+    pub fn held_piece(&self) -> Option<&PieceType> {
+        self.held_piece.as_ref()
+    }
+
+    pub fn hold_rule(&self) -> &HoldRule {
+        &self.hold_rule
+    }
+
+    pub fn set_hold_rule(&mut self, hold_rule: HoldRule) {
+        self.hold_rule = hold_rule;
+    }
+
+    pub fn set_lock_delay(&mut self, lock_delay: LockDelay) {
+        self.lock_delay = lock_delay;
+    }
+
+    pub fn can_hold(&self) -> bool {
+        match self.hold_rule {
+            HoldRule::Guideline => !self.hold_used && !self.done,
+            HoldRule::Swap => !self.done,
+        }
+    }
+
+    pub fn done(&self) -> bool {
+        self.done
+    }
+
+    pub fn score(&self) -> u32 {
+        self.score
+    }
+
+    pub fn lines_cleared(&self) -> u32 {
+        self.lines_cleared
+    }
+
+    pub fn set_scoring(&mut self, scoring: Box<dyn Scoring>) {
+        self.scoring = scoring;
+    }
+
+    // the most recent line clear, until the next one happens
+    pub fn last_clear(&self) -> Option<&LineClear> {
+        self.last_clear.as_ref()
+    }
+
+    pub fn pieces_locked(&self) -> u32 {
+        self.stats.pieces
+    }
+
+    // extra keys pressed over the whole game, only pieces that didn't need a soft drop count
+    pub fn finesse_faults(&self) -> u32 {
+        self.finesse_faults
+    }
+
+    // how the most recent piece was played, until the next one locks
+    pub fn last_finesse(&self) -> Option<&Finesse> {
+        self.last_finesse.as_ref()
+    }
+
+    // time the game has been running, not counting pauses
+    pub fn play_time(&self) -> Duration {
+        self.stats.time
+    }
+
+    pub fn stats(&self) -> &Stats {
+        &self.stats
+    }
+
+    pub fn combo(&self) -> Option<u32> {
+        self.combo
+    }
+
+    pub fn level(&self) -> u32 {
+        self.start_level + self.lines_cleared / 10
+    }
+
+    pub fn set_gravity_curve(&mut self, gravity_curve: GravityCurve) {
+        // start level goes back to the first level of the new curve, set it again afterwards if needed
+        self.start_level = gravity_curve.first_level();
+        self.gravity_curve = gravity_curve;
+    }
+
+    pub fn set_start_level(&mut self, start_level: u32) {
+        self.start_level = start_level.max(self.gravity_curve.first_level());
+    }
+
+    pub fn gravity(&self) -> Gravity {
+        self.gravity_curve.gravity(self.level())
+    }
+
+    pub fn set_square(&mut self, x: usize, y: usize, square: Square) {
+        self.board[y][x] = Some(square);
+    }
+    pub fn get_square(&self, x: usize, y: usize) -> Option<&Square> {
+        self.board[y][x].as_ref()
+    }
+    pub fn get_square_hue(&self, x: usize, y: usize) -> Option<f32> {
+        self.get_square(x, y).map(Square::to_hue)
+    }
+
+    pub fn square_filled(&self, x: usize, y: usize) -> bool {
+        self.board[y][x].is_some()
+    }
+
+    pub fn check_valid_piece_position(&self, piece: &Piece) -> bool {
+        for (x, y) in piece.squares() {
+            if !self.open_square((x, y)) {
+                return false;
+            }
+        }
+        true
+    }
+
+    pub fn hold_piece(&mut self) {
+        if !self.can_hold() {
+            return;
+        }
+        match self.hold_rule {
+            HoldRule::Guideline => self.hold_and_respawn(),
+            HoldRule::Swap => self.hold_in_place(),
+        }
+    }
+
+    fn hold_and_respawn(&mut self) {
+        // first hold of the game takes the next piece from the queue, after that it swaps with the held piece
+        let new_piece_type = match self.held_piece.take() {
+            Some(held_piece) => held_piece,
+            None => self.pop_next_piece(),
+        };
+        self.held_piece = Some(self.active_piece.piece_type.clone());
+        self.hold_used = true;
+        self.stats.holds += 1;
+        self.spawn_piece(new_piece_type);
+    }
+
+    fn hold_in_place(&mut self) {
+        let mut new_active_piece = self.active_piece.clone();
+        new_active_piece.piece_type = match &self.held_piece {
+            Some(held_piece) => held_piece.clone(),
+            None => self.next_pieces[0].clone(),
+        };
+        if self.check_valid_piece_position(&new_active_piece) {
+            if self.held_piece.is_none() {
+                self.pop_next_piece();
+            }
+            self.piece_start = new_active_piece.clone();
+            self.piece_keys.clear();
+            let old_active_piece = std::mem::replace(&mut self.active_piece, new_active_piece);
+            self.held_piece = Some(old_active_piece.piece_type);
+            self.stats.holds += 1;
+        }
+    }
+
+    pub fn instant_drop_piece(&self) -> Piece {
+        self.dropped(&self.active_piece)
+    }
+
+    // where a piece would land, without touching the board. For the ghost, bots etc.
+    pub fn dropped(&self, piece: &Piece) -> Piece {
+        let mut phantom_piece = piece.clone();
+        for y in (-1..piece.position.1).rev() {
+            // check from -1 since turned pieces can have negative y-pos while being inside the board
+            phantom_piece.position.1 = y;
+            if !self.check_valid_piece_position(&phantom_piece) {
+                phantom_piece.position.1 += 1;
+                return phantom_piece;
+            }
+        }
+        piece.clone() // fallback, but shouldn't be necessary
+    }
+
+    pub fn do_instant_drop(&mut self) {
+        if self.done {
+            return;
+        }
+        let drop_piece = self.instant_drop_piece();
+        let cells = (self.active_piece.position.1 - drop_piece.position.1) as u32;
+        self.score += self.scoring.hard_drop(cells);
+        self.active_piece = drop_piece;
+        // hard drop skips the lock delay
+        self.lock_and_renew_active_piece();
+    }
+
+    // player-initiated tick, gives points for every cell the piece goes down
+    pub fn soft_drop(&mut self) {
+        if self.done {
+            return;
+        }
+        let y_before = self.active_piece.position.1;
+        self.tick();
+        if self.active_piece.position.1 == y_before - 1 {
+            self.score += self.scoring.soft_drop(1);
+        }
+    }
+
+    pub fn tick(&mut self) {
+        let piece_moved = self.move_piece(Direction::Down);
+        if !piece_moved {
+            if self.lock_delay.delay.is_zero() {
+                self.lock_and_renew_active_piece();
+            } else {
+                // piece landed, advance() locks it once the delay runs out
+                self.lock_timer.get_or_insert(Duration::ZERO);
+            }
+        }
+    }
+
+    // lets the lock delay and the game clock run, independently of gravity ticks. Returns whether the piece locked
+    pub fn advance(&mut self, elapsed: Duration) -> bool {
+        if self.done {
+            return false;
+        }
+        self.stats.time += elapsed;
+        if self.check_goal() {
+            return false; // time's up
+        }
+        if !self.is_grounded() {
+            // kicked up or slid off a ledge. The timer waits rather than starting over, only a new
+            // lowest row gives a fresh one, otherwise a piece could stay up forever
+            return false;
+        }
+        let lock_timer = self.lock_timer.get_or_insert(Duration::ZERO);
+        *lock_timer += elapsed;
+        if *lock_timer < self.lock_delay.delay {
+            return false;
+        }
+        self.lock_and_renew_active_piece();
+        true
+    }
+
+    pub fn is_grounded(&self) -> bool {
+        self.active_piece
+            .squares_after_move(Direction::Down)
+            .into_iter()
+            .any(|square| !self.open_square(square))
+    }
+
+    pub fn move_piece(&mut self, direction: Direction) -> bool {
+        let Some(moved_piece) = self.moved(&self.active_piece, direction.clone()) else {
+            return false;
+        };
+        let moved_down = direction == Direction::Down;
+        self.active_piece = moved_piece;
+        self.last_rotation = None;
+        if moved_down {
+            self.on_piece_moved_down();
+        } else {
+            self.on_piece_moved();
+        }
+        true
+    }
+
+    fn on_piece_moved(&mut self) {
+        // successful sideways move or rotation
+        if self.lock_delay.reset == LockReset::Move
+            && self.lock_timer.is_some()
+            && self.lock_resets < self.lock_delay.max_resets
+        {
+            self.lock_timer = Some(Duration::ZERO);
+            self.lock_resets += 1;
+        }
+    }
+
+    fn on_piece_moved_down(&mut self) {
+        // reaching a new lowest row gives a fresh timer and all resets back
+        if self.active_piece.position.1 < self.lowest_y {
+            self.lowest_y = self.active_piece.position.1;
+            self.lock_timer = None;
+            self.lock_resets = 0;
+        }
+    }
+
+    pub fn in_range(&self, (x, y): (i32, i32)) -> bool {
+        x >= 0 && x < self.width as i32 && y >= 0 && y < self.height as i32
+    }
+
+    pub fn open_square(&self, (x, y): (i32, i32)) -> bool {
+        // checks if square is in range and free. Safe alternative to square_filled
+
+        // self.in_range((x, y)) && !self.get_square(x as usize, y as usize) // TODO: choose (x,y) vs x,y in function signatures
+        if !self.in_range((x, y)) {
+            return false;
+        }
+        !self.square_filled(x as usize, y as usize)
+    }
+
+    pub fn rotate_piece(&mut self, clockwise: bool) {
+        let rotation = if clockwise {
+            Rotation::Clockwise
+        } else {
+            Rotation::CounterClockwise
+        };
+        self.rotate(rotation);
+    }
+
+    pub fn rotate_piece_180(&mut self) {
+        self.rotate(Rotation::Half);
+    }
+
+    fn rotate(&mut self, rotation: Rotation) {
+        if let Some((rotated_piece, kick)) = self.rotated(&self.active_piece, &rotation) {
+            self.active_piece = rotated_piece;
+            self.last_rotation = Some((rotation, kick));
+            self.on_piece_moved();
+        }
+    }
+
+    // the same rules as move_piece and rotate_piece, for any piece and without changing anything
+    pub fn moved(&self, piece: &Piece, direction: Direction) -> Option<Piece> {
+        for (x, y) in piece.squares_after_move(direction.clone()) {
+            if !self.open_square((x, y)) {
+                return None;
+            }
+        }
+        let mut moved_piece = piece.clone();
+        moved_piece.move_in_direction(direction);
+        Some(moved_piece)
+    }
+
+    // also gives the index of the kick that was used
+    pub fn rotated(&self, piece: &Piece, rotation: &Rotation) -> Option<(Piece, usize)> {
+        let jumps = piece.kicks(rotation);
+        let mut rotated_piece = piece.clone();
+        rotated_piece.rotate(rotation);
+
+        for (kick, jump) in jumps.into_iter().enumerate() {
+            rotated_piece.translate(jump);
+            if rotated_piece
+                .squares()
+                .iter()
+                .all(|&(x, y)| self.open_square((x, y)))
+            {
+                return Some((rotated_piece, kick));
+            }
+            rotated_piece.translate((-jump.0, -jump.1)) // TODO: keep this way or do a "squares_after_translate" method?
+                                                        // in which case maybe redo whole method
+        }
+        None
+    }
+
+    fn lock_and_renew_active_piece(&mut self) {
+        // locks previous piece in place and makes a new one
+        if self.done {
+            return;
+        }
+
+        // judged against the stack it was played on, before it's part of it
+        let optimal = self.finesse(&self.piece_start, &self.active_piece);
+        for (x, y) in self.active_piece.squares() {
+            // a piece put in place with set_active_piece can hang off the board
+            if self.in_range((x, y)) {
+                self.set_square(
+                    x as usize,
+                    y as usize,
+                    Square::Piece(self.active_piece.piece_type.clone()),
+                );
+            }
+        }
+        self.stats.add_piece(&self.active_piece.piece_type);
+        if let Some(optimal) = optimal {
+            let finesse = Finesse {
+                piece_number: self.stats.pieces,
+                piece: self.active_piece.clone(),
+                keys: std::mem::take(&mut self.piece_keys),
+                optimal,
+            };
+            self.finesse_faults += finesse.faults();
+            self.last_finesse = Some(finesse);
+        }
+        let t_spin = self.detect_t_spin();
+        self.clear_full_rows(t_spin);
+        if self.check_goal() {
+            return; // no next piece after the last line
+        }
+        if self.game_mode.clears_every_piece() {
+            self.board = vec![vec![None; self.width]; self.height];
+        }
+        self.hold_used = false;
+        let next_piece_type = self.pop_next_piece();
+        self.spawn_piece(next_piece_type);
+    }
+
+    // how a piece of this type comes into play
+    pub fn new_piece(&self, piece_type: PieceType) -> Piece {
+        Piece {
+            position: (
+                (self.width / 2) as i32,
+                self.height.saturating_sub(2) as i32,
+            ),
+            piece_type,
+            orientation: Orientation::Deg0,
+            rotation_system: self.rotation_system,
+        }
+    }
+
+    fn spawn_piece(&mut self, piece_type: PieceType) {
+        let new_piece = self.new_piece(piece_type);
+        for (x, y) in new_piece.squares() {
+            if self.in_range((x, y)) && self.square_filled(x as usize, y as usize) {
+                // new piece placed onto occupied square
+                self.done = true;
+            }
+        }
+        self.lowest_y = new_piece.position.1;
+        self.last_rotation = None;
+        self.lock_timer = None;
+        self.lock_resets = 0;
+        self.gravity_cells = 0.;
+        self.piece_start = new_piece.clone();
+        self.piece_keys.clear();
+        self.active_piece = new_piece;
+    }
+
+    // the piece that's on exactly these squares, out of the types and orientations given
+    fn piece_covering(
+        &self,
+        squares: &[(i32, i32)],
+        piece_type: Option<PieceType>,
+        orientation: Option<Orientation>,
+    ) -> Option<Piece> {
+        use Orientation as Or;
+        let piece_types = piece_type.map_or(PieceType::ALL.to_vec(), |piece_type| vec![piece_type]);
+        let orientations = orientation.map_or(
+            vec![Or::Deg0, Or::Deg90, Or::Deg180, Or::Deg270],
+            |orientation| vec![orientation],
+        );
+        let mut wanted = squares.to_vec();
+        wanted.sort();
+        for piece_type in piece_types {
+            for orientation in &orientations {
+                let mut piece = Piece {
+                    position: (0, 0),
+                    piece_type: piece_type.clone(),
+                    orientation: orientation.clone(),
+                    rotation_system: self.rotation_system,
+                };
+                // any square of the shape could be the first wanted one
+                for (dx, dy) in piece.squares() {
+                    piece.position = (wanted[0].0 - dx, wanted[0].1 - dy);
+                    let mut placed = piece.squares();
+                    placed.sort();
+                    if placed == wanted {
+                        return Some(piece);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn detect_t_spin(&self) -> TSpin {
+        // 3-corner rule: a rotated T with at least 3 of the 4 diagonal corners filled.
+        // If only one of the corners it points at is filled it's a mini, unless SRS's last kick was used
+        let Some((rotation, kick)) = &self.last_rotation else {
+            return TSpin::None;
+        };
+        if self.active_piece.piece_type != PieceType::T {
+            return TSpin::None;
+        }
+        // works out the middle and the nub from the squares, since not every rotation system
+        // has the middle of the T at the piece position
+        let squares = self.active_piece.squares();
+        let is_neighbour =
+            |(ax, ay): (i32, i32), (bx, by): (i32, i32)| (ax - bx).abs() + (ay - by).abs() == 1;
+        let Some(&(x, y)) = squares.iter().find(|&&square| {
+            squares
+                .iter()
+                .filter(|&&other| is_neighbour(square, other))
+                .count()
+                == 3
+        }) else {
+            return TSpin::None;
+        };
+        let Some(&(nub_x, nub_y)) = squares
+            .iter()
+            .find(|&&(sx, sy)| (sx, sy) != (x, y) && !squares.contains(&(2 * x - sx, 2 * y - sy)))
+        else {
+            return TSpin::None;
+        };
+        let (dx, dy) = (nub_x - x, nub_y - y); // direction the T points in
+
+        let filled = |(cx, cy): (i32, i32)| !self.open_square((x + cx, y + cy)); // walls count as filled
+        let corners = [(-1, 1), (1, 1), (1, -1), (-1, -1)];
+        if corners.into_iter().filter(|&corner| filled(corner)).count() < 3 {
+            return TSpin::None;
+        }
+        let front_corners = [(dx + dy, dy - dx), (dx - dy, dy + dx)];
+        if front_corners.into_iter().all(filled) || (*rotation != Rotation::Half && *kick == 4) {
+            TSpin::Full
+        } else {
+            TSpin::Mini
+        }
+    }
+
+    fn clear_full_rows(&mut self, t_spin: TSpin) {
+        let mut filled_rows = Vec::new();
+        for (row_nr, row) in self.board.iter().enumerate() {
+            if row.iter().all(|x| x.is_some()) {
+                filled_rows.push(row_nr);
+            }
+        }
+
+        if filled_rows.is_empty() {
+            self.combo = None;
+            if t_spin != TSpin::None {
+                // t-spins without lines still score, but don't touch back-to-back
+                let clear = LineClear {
+                    lines: 0,
+                    t_spin,
+                    back_to_back: false,
+                    combo: 0,
+                    piece_number: self.stats.pieces,
+                };
+                self.score += self.scoring.line_clear(&clear, self.level());
+                self.stats.add_clear(&clear);
+                self.last_clear = Some(clear);
+            }
+            return;
+        }
+
+        let combo = self.combo.map_or(0, |combo| combo + 1);
+        let mut clear = LineClear {
+            lines: filled_rows.len() as u32,
+            t_spin,
+            back_to_back: false,
+            combo,
+            piece_number: self.stats.pieces,
+        };
+        clear.back_to_back = clear.is_difficult() && self.back_to_back;
+        self.back_to_back = clear.is_difficult();
+        self.combo = Some(combo);
+        // points use the level before these lines count towards it
+        self.score += self.scoring.line_clear(&clear, self.level());
+        self.lines_cleared += clear.lines;
+        self.stats.add_clear(&clear);
+        self.last_clear = Some(clear);
+        for row_nr in filled_rows.into_iter().rev() {
+            self.board.remove(row_nr);
+            self.board.push(vec![None; self.width]);
+        }
+    }
+}
+
+// "{board}" draws the playfield with '*' for locked squares and '+' for the active piece.
+// "{board:#}" writes everything FromStr reads back: letters for locked squares (G for garbage),
+// the active piece in lower case, and the seed, hold, next queue and orientation above the playfield
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let letters = f.alternate();
+        if letters {
+            writeln!(f, "seed {}", self.seed)?;
+            if let Some(held_piece) = &self.held_piece {
+                writeln!(f, "hold {}", held_piece.letter())?;
+            }
+            let next: String = self.next_pieces.iter().map(PieceType::letter).collect();
+            writeln!(f, "next {next}")?;
+            writeln!(f, "orientation {}", self.active_piece.orientation.degrees())?;
+        }
+        let active_squares = self.active_piece.squares();
+        let board_string = (0..self.height)
+            .rev()
+            .map(|y| {
+                let mut line = String::new();
+                for x in 0..self.width {
+                    if let Some(square) = self.get_square(x, y) {
+                        line.push(if letters { square.letter() } else { '*' });
+                    } else if active_squares.contains(&(x as i32, y as i32)) {
+                        let letter = self.active_piece.piece_type.letter().to_ascii_lowercase();
+                        line.push(if letters { letter } else { '+' });
+                    } else {
+                        line.push(' ')
+                    }
+                }
+                line
+            })
+            .map(|line| format!("|{line}|"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let mut top_border = "+".to_owned();
+        top_border.push_str(&"-".repeat(self.width));
+        top_border.push_str("+\n");
+        write!(f, "{}{}\n{}", top_border, board_string, top_border)
+    }
+}
+
+fn piece_type_from_letter(letter: char) -> Result<PieceType, String> {
+    PieceType::from_letter(letter).ok_or(format!("unknown piece {letter:?}"))
+}
+
+// reads both formats Display writes, for test fixtures, bug reports and puzzles. The lines above
+// the playfield are all optional: without a seed it's random, a short next queue gets dealt on
+// from the randomizer, and without an orientation the active piece's is worked out from its shape.
+// Without an active piece the first next piece spawns as usual
+impl FromStr for Board {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut seed = random();
+        let mut held_piece = None;
+        let mut next_pieces = Vec::new();
+        let mut orientation = None;
+        let mut rows = Vec::new();
+        for line in s.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            if let Some(row) = line.strip_prefix('|') {
+                let row = row
+                    .strip_suffix('|')
+                    .ok_or(format!("row without a right border: {line:?}"))?;
+                rows.push(row);
+                continue;
+            }
+            if line.starts_with('+') {
+                continue; // top or bottom border
+            }
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            let value = value.trim();
+            match key {
+                "seed" => seed = value.parse().map_err(|_| format!("bad seed {value:?}"))?,
+                "hold" => {
+                    let mut letters = value.chars();
+                    held_piece = match (letters.next(), letters.next()) {
+                        (Some(letter), None) => Some(piece_type_from_letter(letter)?),
+                        _ => return Err(format!("hold takes one piece, not {value:?}")),
+                    }
+                }
+                "next" => {
+                    next_pieces = value
+                        .chars()
+                        .map(piece_type_from_letter)
+                        .collect::<Result<_, _>>()?
+                }
+                "orientation" => {
+                    orientation = Some(
+                        value
+                            .parse()
+                            .ok()
+                            .and_then(Orientation::from_degrees)
+                            .ok_or(format!("bad orientation {value:?}"))?,
+                    )
+                }
+                _ => return Err(format!("unknown line {line:?}")),
+            }
+        }
+        if next_pieces.len() > MAX_PREVIEW_LENGTH {
+            return Err(format!("at most {MAX_PREVIEW_LENGTH} next pieces"));
+        }
+
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| row.chars().count());
+        if width == 0 {
+            return Err("no playfield".to_owned());
+        }
+        if let Some(row_nr) = rows.iter().position(|row| row.chars().count() != width) {
+            return Err(format!("row {} isn't {width} wide", row_nr + 1));
+        }
+        let mut board = Board::with_seed(width, height, seed)?;
+        let mut active_squares = Vec::new();
+        let mut active_type = None;
+        for (row_nr, row) in rows.iter().enumerate() {
+            let y = height - 1 - row_nr; // first row is the top
+            for (x, letter) in row.chars().enumerate() {
+                match letter {
+                    ' ' => {}
+                    '*' | 'G' => board.set_square(x, y, Square::Garbage),
+                    '+' => active_squares.push((x as i32, y as i32)),
+                    _ if letter.is_ascii_lowercase() => {
+                        let piece_type = piece_type_from_letter(letter.to_ascii_uppercase())?;
+                        if active_type.get_or_insert(piece_type.clone()) != &piece_type {
+                            return Err("the active piece has two different letters".to_owned());
+                        }
+                        active_squares.push((x as i32, y as i32));
+                    }
+                    _ => board.set_square(x, y, Square::Piece(piece_type_from_letter(letter)?)),
+                }
+            }
+        }
+
+        for (next_piece, piece_type) in board.next_pieces.iter_mut().zip(next_pieces) {
+            *next_piece = piece_type;
+        }
+        board.held_piece = held_piece;
+        if active_squares.is_empty() {
+            let piece_type = board.pop_next_piece();
+            board.spawn_piece(piece_type);
+        } else {
+            let piece = board
+                .piece_covering(&active_squares, active_type, orientation)
+                .ok_or("the active squares don't make a piece")?;
+            board.set_active_piece(piece);
+        }
+        Ok(board)
+    }
+}
@@ -0,0 +1,134 @@
+use crate::board::{Board, HoldRule};
+use crate::input::Action;
+use crate::piece::Piece;
+
+// how much each feature of the stack after a placement counts, El-Tetris style. Negative is bad
+#[derive(Clone, Debug, PartialEq)]
+pub struct Weights {
+    pub aggregate_height: f32, // heights of all columns added up
+    pub lines_cleared: f32,
+    pub holes: f32,     // empty squares with something above them
+    pub bumpiness: f32, // height differences between neighbouring columns
+    pub wells: f32,     // how far columns are below both neighbours, walls count as high
+}
+
+impl Default for Weights {
+    // Yiyuan Lee's tuned weights, with a little extra against deep wells
+    fn default() -> Self {
+        Self {
+            aggregate_height: -0.510066,
+            lines_cleared: 0.760666,
+            holes: -0.35663,
+            bumpiness: -0.184483,
+            wells: -0.05,
+        }
+    }
+}
+
+// where the bot wants the piece to go and the keys that get it there
+#[derive(Clone, Debug, PartialEq)]
+pub struct Plan {
+    pub piece: Piece,        // where it locks
+    pub inputs: Vec<Action>, // starts with Hold if it's the other piece, ends with the hard drop
+    pub score: f32,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Bot {
+    pub weights: Weights,
+}
+
+impl Bot {
+    pub fn new(weights: Weights) -> Self {
+        Self { weights }
+    }
+
+    // best placement of the active or the held piece, None once the game is over
+    pub fn plan(&self, board: &Board) -> Option<Plan> {
+        if board.done() {
+            return None;
+        }
+        let mut plans = self.placements(board, board.active_piece().clone(), Vec::new());
+        // the first hold takes the next piece instead
+        let hold_type = board.held_piece().or(board.next_pieces().next()).cloned();
+        if let Some(piece_type) = hold_type.filter(|_| board.can_hold()) {
+            let held_piece = match board.hold_rule() {
+                HoldRule::Guideline => board.new_piece(piece_type),
+                HoldRule::Swap => Piece {
+                    piece_type,
+                    ..board.active_piece().clone()
+                },
+            };
+            if board.check_valid_piece_position(&held_piece) {
+                plans.extend(self.placements(board, held_piece, vec![Action::Hold]));
+            }
+        }
+        // fewer inputs wins a tie
+        plans.into_iter().max_by(|a, b| {
+            a.score
+                .total_cmp(&b.score)
+                .then(b.inputs.len().cmp(&a.inputs.len()))
+        })
+    }
+
+    // everywhere the move generator can get the piece to, tucks and spins included
+    fn placements(&self, board: &Board, start: Piece, inputs: Vec<Action>) -> Vec<Plan> {
+        board
+            .placements(&start)
+            .into_iter()
+            .map(|placement| Plan {
+                score: self.evaluate(board, &placement.piece),
+                inputs: [inputs.clone(), placement.inputs].concat(),
+                piece: placement.piece,
+            })
+            .collect()
+    }
+
+    // how good the stack looks with this piece locked where it is
+    pub fn evaluate(&self, board: &Board, piece: &Piece) -> f32 {
+        let squares = piece.squares();
+        if squares.iter().any(|&square| !board.in_range(square)) {
+            return f32::NEG_INFINITY; // sticks out the top
+        }
+        let width = board.width();
+        let mut rows: Vec<Vec<bool>> = (0..board.height())
+            .map(|y| (0..width).map(|x| board.square_filled(x, y)).collect())
+            .collect();
+        for (x, y) in squares {
+            rows[y as usize][x as usize] = true;
+        }
+        let full_rows = rows
+            .iter()
+            .filter(|row| row.iter().all(|&filled| filled))
+            .count();
+        rows.retain(|row| !row.iter().all(|&filled| filled));
+
+        let heights: Vec<usize> = (0..width)
+            .map(|x| rows.iter().rposition(|row| row[x]).map_or(0, |y| y + 1))
+            .collect();
+        let holes: usize = (0..width)
+            .map(|x| (0..heights[x]).filter(|&y| !rows[y][x]).count())
+            .sum();
+        let bumpiness: usize = heights
+            .windows(2)
+            .map(|pair| pair[0].abs_diff(pair[1]))
+            .sum();
+        let wells: usize = (0..width)
+            .map(|x| {
+                let left = x.checked_sub(1).map_or(usize::MAX, |left| heights[left]);
+                let right = heights.get(x + 1).copied().unwrap_or(usize::MAX);
+                match left.min(right) {
+                    usize::MAX => 0, // one column wide board
+                    lowest => lowest.saturating_sub(heights[x]),
+                }
+            })
+            .sum();
+
+        let weights = &self.weights;
+        weights.aggregate_height * heights.iter().sum::<usize>() as f32
+            + weights.lines_cleared * full_rows as f32
+            + weights.holes * holes as f32
+            + weights.bumpiness * bumpiness as f32
+            + weights.wells * wells as f32
+    }
+}
@@ -0,0 +1,19 @@
+// how every front end shows game times
+use std::time::Duration;
+
+// m:ss, for clocks that are still running
+pub fn format_seconds(time: Duration) -> String {
+    let seconds = time.as_secs();
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+// m:ss.cc, precise enough to compare sprint times
+pub fn format_time(time: Duration) -> String {
+    let centiseconds = time.as_millis() / 10;
+    format!(
+        "{}:{:02}.{:02}",
+        centiseconds / 6000,
+        centiseconds / 100 % 60,
+        centiseconds % 100
+    )
+}
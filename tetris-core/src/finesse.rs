@@ -0,0 +1,146 @@
+use std::collections::{HashSet, VecDeque};
+
+use rand::{seq::SliceRandom, RngCore};
+
+use crate::board::Board;
+use crate::input::Action;
+use crate::piece::Piece;
+use crate::rotation::Rotation;
+
+// one key press when counting finesse
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Press {
+    Tap(Action),
+    Das(Action), // held until the piece hits something, still just one key
+}
+
+// how the keys pressed for one piece compare to the fewest that would have done it
+#[derive(Clone, Debug, PartialEq)]
+pub struct Finesse {
+    pub piece_number: u32, // pieces_locked once this piece locked, like LineClear
+    pub piece: Piece,      // where it locked
+    pub keys: Vec<Action>, // moves and rotations pressed, drops don't count
+    pub optimal: Vec<Press>,
+}
+
+impl Finesse {
+    // extra keys pressed, 0 for a clean piece
+    pub fn faults(&self) -> u32 {
+        self.keys.len().saturating_sub(self.optimal.len()) as u32
+    }
+}
+
+// where the finesse trainer wants the active piece, a new one for every piece that comes into play
+// including after a hold
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FinesseTarget {
+    pub piece: Option<Piece>,
+    pieces_locked: u32, // when it was picked
+}
+
+impl FinesseTarget {
+    // picks a random target if the active piece isn't the one this was picked for, true if it did
+    pub fn update(&mut self, board: &Board, rng: &mut dyn RngCore) -> bool {
+        let current = self.piece.as_ref().is_some_and(|piece| {
+            piece.piece_type == board.active_piece().piece_type
+                && self.pieces_locked == board.pieces_locked()
+        });
+        if current {
+            return false;
+        }
+        self.piece = board.finesse_targets().choose(rng).cloned();
+        self.pieces_locked = board.pieces_locked();
+        true
+    }
+
+    // whether the piece that just locked landed on the target, before it's updated
+    pub fn hit(&self, board: &Board) -> bool {
+        let (Some(target), Some(finesse)) = (&self.piece, board.last_finesse()) else {
+            return false;
+        };
+        let mut landed = finesse.piece.squares();
+        let mut wanted = target.squares();
+        landed.sort();
+        wanted.sort();
+        finesse.piece_number == self.pieces_locked + 1
+            && finesse.piece_number == board.pieces_locked()
+            && landed == wanted
+    }
+}
+
+// in the order they're tried, so a tie goes to DAS, then tapping, then rotating
+const PRESSES: [Press; 7] = [
+    Press::Das(Action::MoveLeft),
+    Press::Das(Action::MoveRight),
+    Press::Tap(Action::MoveLeft),
+    Press::Tap(Action::MoveRight),
+    Press::Tap(Action::RotateCW),
+    Press::Tap(Action::RotateCCW),
+    Press::Tap(Action::Rotate180),
+];
+
+impl Board {
+    // fewest moves and rotations that get the piece over the target so a hard drop lands on its
+    // squares, all up where it started. None if it needs a soft drop, tucks and spins aren't a
+    // finesse thing
+    pub fn finesse(&self, start: &Piece, target: &Piece) -> Option<Vec<Press>> {
+        let mut target_squares = target.squares();
+        target_squares.sort();
+        let key = |piece: &Piece| (piece.position, piece.orientation.degrees());
+        let mut seen = HashSet::from([key(start)]);
+        let mut queue = VecDeque::from([(start.clone(), Vec::new())]);
+        while let Some((piece, presses)) = queue.pop_front() {
+            let mut squares = self.dropped(&piece).squares();
+            squares.sort();
+            if squares == target_squares {
+                return Some(presses);
+            }
+            for press in PRESSES {
+                let Some(next) = self.pressed(&piece, press) else {
+                    continue;
+                };
+                // kicks can work a piece down into the stack too, that's a tuck or a spin as well
+                if next.position.1 < start.position.1 {
+                    continue;
+                }
+                if seen.insert(key(&next)) {
+                    let mut presses = presses.clone();
+                    presses.push(press);
+                    queue.push_back((next, presses));
+                }
+            }
+        }
+        None
+    }
+
+    // where the finesse trainer can ask for the active piece to go
+    pub fn finesse_targets(&self) -> Vec<Piece> {
+        let start = self.active_piece();
+        self.placements(start)
+            .into_iter()
+            .map(|placement| placement.piece)
+            .filter(|piece| self.finesse(start, piece).is_some())
+            .collect()
+    }
+
+    fn pressed(&self, piece: &Piece, press: Press) -> Option<Piece> {
+        match press {
+            Press::Tap(Action::RotateCW) => self.rotated(piece, &Rotation::Clockwise),
+            Press::Tap(Action::RotateCCW) => self.rotated(piece, &Rotation::CounterClockwise),
+            Press::Tap(Action::Rotate180) => self.rotated(piece, &Rotation::Half),
+            Press::Tap(action) => action
+                .direction()
+                .and_then(|direction| self.moved(piece, direction))
+                .map(|piece| (piece, 0)),
+            Press::Das(action) => {
+                let direction = action.direction()?;
+                let mut piece = self.moved(piece, direction.clone())?;
+                while let Some(further) = self.moved(&piece, direction.clone()) {
+                    piece = further;
+                }
+                Some((piece, 0))
+            }
+        }
+        .map(|(piece, _kick)| piece)
+    }
+}
@@ -0,0 +1,172 @@
+use std::{fmt, time::Duration};
+
+use crate::board::Board;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Goal {
+    Lines(u32),     // finished after clearing this many lines
+    Time(Duration), // finished when the clock runs out
+    None,           // keeps going until topping out
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Ranking {
+    Score, // higher is better
+    Time,  // lower is better, only finished games count
+}
+
+// what you're playing for: when the game ends and what makes a result good
+pub trait GameMode {
+    fn name(&self) -> &'static str;
+
+    fn description(&self) -> &'static str;
+
+    fn goal(&self) -> Goal;
+
+    fn ranking(&self) -> Ranking;
+
+    fn goal_reached(&self, board: &Board) -> bool {
+        match self.goal() {
+            Goal::Lines(lines) => board.lines_cleared() >= lines,
+            Goal::Time(time) => board.play_time() >= time,
+            Goal::None => false,
+        }
+    }
+
+    // a sprint that tops out has no time to compare, so it doesn't go on the leaderboard
+    fn is_ranked(&self, board: &Board) -> bool {
+        match self.ranking() {
+            Ranking::Score => true,
+            Ranking::Time => board.goal_reached(),
+        }
+    }
+
+    // the board empties after every piece, for practising placements rather than stacking
+    fn clears_every_piece(&self) -> bool {
+        false
+    }
+}
+
+impl fmt::Debug for dyn GameMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl PartialEq for dyn GameMode {
+    fn eq(&self, other: &Self) -> bool {
+        self.name() == other.name()
+    }
+}
+
+pub const GAME_MODES: [&dyn GameMode; 5] = [&Marathon, &Sprint, &Ultra, &Endless, &FinesseTrainer];
+
+pub struct Marathon;
+
+impl GameMode for Marathon {
+    fn name(&self) -> &'static str {
+        "Marathon"
+    }
+
+    fn description(&self) -> &'static str {
+        "clear 150 lines while the game speeds up"
+    }
+
+    fn goal(&self) -> Goal {
+        Goal::Lines(150)
+    }
+
+    fn ranking(&self) -> Ranking {
+        Ranking::Score
+    }
+}
+
+pub struct Sprint;
+
+impl GameMode for Sprint {
+    fn name(&self) -> &'static str {
+        "Sprint"
+    }
+
+    fn description(&self) -> &'static str {
+        "clear 40 lines as fast as possible"
+    }
+
+    fn goal(&self) -> Goal {
+        Goal::Lines(40)
+    }
+
+    fn ranking(&self) -> Ranking {
+        Ranking::Time
+    }
+}
+
+pub struct Ultra;
+
+impl GameMode for Ultra {
+    fn name(&self) -> &'static str {
+        "Ultra"
+    }
+
+    fn description(&self) -> &'static str {
+        "score as much as possible in 2 minutes"
+    }
+
+    fn goal(&self) -> Goal {
+        Goal::Time(Duration::from_secs(120))
+    }
+
+    fn ranking(&self) -> Ranking {
+        Ranking::Score
+    }
+}
+
+pub struct Endless;
+
+impl GameMode for Endless {
+    fn name(&self) -> &'static str {
+        "Endless"
+    }
+
+    fn description(&self) -> &'static str {
+        "play until you top out"
+    }
+
+    fn goal(&self) -> Goal {
+        Goal::None
+    }
+
+    fn ranking(&self) -> Ranking {
+        Ranking::Score
+    }
+}
+
+// one piece at a time on an empty board, each with a target to reach in as few keys as possible
+pub struct FinesseTrainer;
+
+impl GameMode for FinesseTrainer {
+    fn name(&self) -> &'static str {
+        "Finesse"
+    }
+
+    fn description(&self) -> &'static str {
+        "put each piece on its target with as few keys as possible"
+    }
+
+    fn goal(&self) -> Goal {
+        Goal::None
+    }
+
+    fn ranking(&self) -> Ranking {
+        Ranking::Score
+    }
+
+    // practice, nothing to rank
+    fn is_ranked(&self, _board: &Board) -> bool {
+        false
+    }
+
+    fn clears_every_piece(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,74 @@
+use std::time::Duration;
+
+pub const FRAMES_PER_SECOND: u32 = 60;
+pub const FRAME_DURATION: Duration = Duration::from_nanos(1_000_000_000 / FRAMES_PER_SECOND as u64);
+// a front end more than this many frames behind (e.g. a long hiccup or a suspended terminal) just
+// carries on from now
+pub const MAX_CATCH_UP_FRAMES: u32 = 10;
+
+// gravity in G, i.e. cells per frame at 60 frames per second. 1/60 G is one cell per second,
+// 20G means the piece drops to the bottom straight away
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Gravity(pub f32);
+
+impl Gravity {
+    pub const MAX: Gravity = Gravity(20.);
+
+    fn from_frames_per_cell(frames: u32) -> Self {
+        Gravity(1. / frames as f32)
+    }
+
+    // time between gravity ticks, never faster than a frame
+    pub fn tick_interval(&self) -> Duration {
+        let frames = (1. / self.0).max(1.);
+        Duration::from_secs_f32(frames / FRAMES_PER_SECOND as f32)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GravityCurve {
+    Guideline, // levels start at 1
+    Nes,       // levels start at 0
+}
+
+impl GravityCurve {
+    pub fn gravity(&self, level: u32) -> Gravity {
+        match self {
+            GravityCurve::Guideline => {
+                // seconds per row = (0.8 - (level - 1) * 0.007)^(level - 1). It's past 20G by level 20,
+                // and much higher the base goes negative and the speed with it
+                let level = level.clamp(1, 20) as f32;
+                let seconds_per_row = (0.8 - (level - 1.) * 0.007).powf(level - 1.);
+                let gravity = 1. / (seconds_per_row * FRAMES_PER_SECOND as f32);
+                Gravity(gravity.min(Gravity::MAX.0))
+            }
+            GravityCurve::Nes => {
+                let frames_per_cell = match level {
+                    0 => 48,
+                    1 => 43,
+                    2 => 38,
+                    3 => 33,
+                    4 => 28,
+                    5 => 23,
+                    6 => 18,
+                    7 => 13,
+                    8 => 8,
+                    9 => 6,
+                    10..=12 => 5,
+                    13..=15 => 4,
+                    16..=18 => 3,
+                    19..=28 => 2,
+                    _ => 1,
+                };
+                Gravity::from_frames_per_cell(frames_per_cell)
+            }
+        }
+    }
+
+    pub fn first_level(&self) -> u32 {
+        match self {
+            GravityCurve::Guideline => 1,
+            GravityCurve::Nes => 0,
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use std::{fs, io, path::PathBuf, time::Duration};
+
+use crate::board::Board;
+use crate::game_mode::{GameMode, Ranking};
+
+pub const MAX_HIGH_SCORES: usize = 10;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct HighScore {
+    pub score: u32,
+    pub lines: u32,
+    pub level: u32,
+    pub duration: Duration,
+    pub date: u64, // milliseconds since the unix epoch, passed in because wasm and native get the time differently
+    pub seed: u64,
+}
+
+impl HighScore {
+    pub fn from_board(board: &Board, date: u64) -> Self {
+        Self {
+            score: board.score(),
+            lines: board.lines_cleared(),
+            level: board.level(),
+            duration: board.play_time(),
+            date,
+            seed: board.seed(),
+        }
+    }
+
+    // "score,lines,level,duration_ms,date,seed"
+    fn to_line(&self) -> String {
+        format!(
+            "{},{},{},{},{},{}",
+            self.score,
+            self.lines,
+            self.level,
+            self.duration.as_millis(),
+            self.date,
+            self.seed
+        )
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        let values: Vec<u64> = line
+            .split(',')
+            .map(|value| value.parse().ok())
+            .collect::<Option<_>>()?;
+        let [score, lines, level, duration, date, seed] = values[..] else {
+            return None;
+        };
+        Some(Self {
+            score: score as u32,
+            lines: lines as u32,
+            level: level as u32,
+            duration: Duration::from_millis(duration),
+            date,
+            seed,
+        })
+    }
+}
+
+// somewhere to keep strings between sessions, e.g. localStorage on the web or files natively.
+// Write errors are left to the front end to report
+pub trait ScoreStorage {
+    fn read(&self, key: &str) -> Option<String>;
+    fn write(&mut self, key: &str, value: &str) -> io::Result<()>;
+}
+
+// one file per key in a directory
+pub struct FileStorage {
+    directory: PathBuf,
+}
+
+impl FileStorage {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+        }
+    }
+}
+
+impl ScoreStorage for FileStorage {
+    fn read(&self, key: &str) -> Option<String> {
+        fs::read_to_string(self.directory.join(key)).ok()
+    }
+
+    fn write(&mut self, key: &str, value: &str) -> io::Result<()> {
+        fs::create_dir_all(&self.directory)?;
+        fs::write(self.directory.join(key), value)
+    }
+}
+
+// best results for one game mode, best first
+pub struct HighScores {
+    game_mode: &'static dyn GameMode,
+    entries: Vec<HighScore>,
+}
+
+impl HighScores {
+    pub fn load(storage: &dyn ScoreStorage, game_mode: &'static dyn GameMode) -> Self {
+        let entries = storage
+            .read(&Self::key(game_mode))
+            .map(|stored| stored.lines().filter_map(HighScore::from_line).collect())
+            .unwrap_or_default();
+        Self { game_mode, entries }
+    }
+
+    pub fn save(&self, storage: &mut dyn ScoreStorage) -> io::Result<()> {
+        let lines: Vec<String> = self.entries.iter().map(HighScore::to_line).collect();
+        storage.write(&Self::key(self.game_mode), &lines.join("\n"))
+    }
+
+    fn key(game_mode: &dyn GameMode) -> String {
+        format!("highscores.{}", game_mode.name().to_lowercase())
+    }
+
+    pub fn game_mode(&self) -> &'static dyn GameMode {
+        self.game_mode
+    }
+
+    fn is_better(&self, high_score: &HighScore, than: &HighScore) -> bool {
+        match self.game_mode.ranking() {
+            Ranking::Score => high_score.score > than.score,
+            Ranking::Time => high_score.duration < than.duration,
+        }
+    }
+
+    pub fn entries(&self) -> &[HighScore] {
+        &self.entries
+    }
+
+    // returns the rank (0 is best) if it made the table. Ties go below the older results
+    pub fn add(&mut self, high_score: HighScore) -> Option<usize> {
+        let rank = self
+            .entries
+            .iter()
+            .position(|entry| self.is_better(&high_score, entry))
+            .unwrap_or(self.entries.len());
+        if rank >= MAX_HIGH_SCORES {
+            return None;
+        }
+        self.entries.insert(rank, high_score);
+        self.entries.truncate(MAX_HIGH_SCORES);
+        Some(rank)
+    }
+}
@@ -0,0 +1,205 @@
+use std::time::Duration;
+
+use crate::board::Board;
+use crate::piece::Direction;
+
+// everything a player can do, independent of which key/button triggers it
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    SoftDrop,
+    HardDrop,
+    RotateCW,
+    RotateCCW,
+    Rotate180,
+    Hold,
+    Pause,
+    Restart,
+}
+
+impl Action {
+    pub const ALL: [Action; 10] = [
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::SoftDrop,
+        Action::HardDrop,
+        Action::RotateCW,
+        Action::RotateCCW,
+        Action::Rotate180,
+        Action::Hold,
+        Action::Pause,
+        Action::Restart,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::MoveLeft => "move left",
+            Action::MoveRight => "move right",
+            Action::SoftDrop => "soft drop",
+            Action::HardDrop => "hard drop",
+            Action::RotateCW => "rotate clockwise",
+            Action::RotateCCW => "rotate counterclockwise",
+            Action::Rotate180 => "rotate 180",
+            Action::Hold => "hold",
+            Action::Pause => "pause",
+            Action::Restart => "restart",
+        }
+    }
+
+    // the held direction for actions that auto-repeat
+    pub fn direction(&self) -> Option<Direction> {
+        match self {
+            Action::MoveLeft => Some(Direction::Left),
+            Action::MoveRight => Some(Direction::Right),
+            Action::SoftDrop => Some(Direction::Down),
+            _ => None,
+        }
+    }
+}
+
+// what the player did during one frame of Board::step
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FrameInput {
+    pub pressed: Vec<Action>, // pressed since the last frame, in order
+    pub held: Vec<Action>, // still down at the end of the frame, only matters for actions that repeat
+}
+
+// how the controls feel, tuned by the player
+#[derive(Clone, Debug, PartialEq)]
+pub struct Handling {
+    pub das: Duration, // delayed auto shift, how long to hold before the piece starts sliding
+    pub arr: Duration, // auto repeat rate, time between moves while sliding. Zero goes straight to the wall
+    pub soft_drop_factor: u32, // how many times faster than gravity soft drop is
+}
+
+impl Default for Handling {
+    fn default() -> Self {
+        Self {
+            das: Duration::from_millis(167),
+            arr: Duration::from_millis(33),
+            soft_drop_factor: 20,
+        }
+    }
+}
+
+// keeps track of which keys are down and repeats moves itself, so the browser/OS key repeat doesn't matter
+#[derive(Default)]
+pub struct AutoShift {
+    pub handling: Handling,
+    left_held: bool,
+    right_held: bool,
+    active: Option<Direction>, // last pressed of left and right wins
+    charge: Duration,          // time the active direction has been held
+    soft_drop_held: bool,
+    soft_drop_timer: Duration,
+}
+
+impl AutoShift {
+    pub fn new(handling: Handling) -> Self {
+        Self {
+            handling,
+            ..Default::default()
+        }
+    }
+
+    // left/right move once straight away, down soft drops once
+    pub fn press(&mut self, board: &mut Board, direction: Direction) {
+        match direction {
+            Direction::Left | Direction::Right => {
+                if direction == Direction::Left {
+                    self.left_held = true;
+                } else {
+                    self.right_held = true;
+                }
+                board.move_piece(direction.clone());
+                self.active = Some(direction);
+                self.charge = Duration::ZERO;
+            }
+            Direction::Down => {
+                self.soft_drop_held = true;
+                self.soft_drop_timer = Duration::ZERO;
+                board.soft_drop();
+            }
+            Direction::Up => {}
+        }
+    }
+
+    pub fn release(&mut self, direction: Direction) {
+        match direction {
+            Direction::Left => self.left_held = false,
+            Direction::Right => self.right_held = false,
+            Direction::Down => self.soft_drop_held = false,
+            Direction::Up => {}
+        }
+        if self.active == Some(direction) {
+            // fall back to the other direction if that one is still held
+            self.active = if self.left_held {
+                Some(Direction::Left)
+            } else if self.right_held {
+                Some(Direction::Right)
+            } else {
+                None
+            };
+            self.charge = Duration::ZERO;
+        }
+    }
+
+    // lets go of everything, e.g. when the window loses focus and keyups would get lost
+    pub fn release_all(&mut self) {
+        self.left_held = false;
+        self.right_held = false;
+        self.active = None;
+        self.soft_drop_held = false;
+    }
+
+    // repeats held moves, returns whether anything happened to the board
+    pub fn update(&mut self, board: &mut Board, elapsed: Duration) -> bool {
+        let mut changed = false;
+
+        if let Some(direction) = self.active.clone() {
+            let charge_before = self.charge;
+            self.charge += elapsed;
+            if self.charge >= self.handling.das {
+                if self.handling.arr.is_zero() {
+                    while board.move_piece(direction.clone()) {
+                        changed = true;
+                    }
+                } else {
+                    // moves that became due this update, counting from the moment das ran out
+                    let repeats_before =
+                        repeats(charge_before, self.handling.das, self.handling.arr);
+                    let repeats_now = repeats(self.charge, self.handling.das, self.handling.arr);
+                    for _ in repeats_before..repeats_now {
+                        changed |= board.move_piece(direction.clone());
+                    }
+                }
+            }
+        }
+
+        if self.soft_drop_held {
+            let interval = board.gravity().tick_interval() / self.handling.soft_drop_factor.max(1);
+            self.soft_drop_timer += elapsed;
+            while self.soft_drop_timer >= interval {
+                if board.done() || board.is_grounded() {
+                    // nothing left to drop, don't save up time for the next piece
+                    self.soft_drop_timer = Duration::ZERO;
+                    break;
+                }
+                self.soft_drop_timer -= interval;
+                board.soft_drop();
+                changed = true;
+            }
+        }
+
+        changed
+    }
+}
+
+fn repeats(charge: Duration, das: Duration, arr: Duration) -> u128 {
+    // the first repeat happens the moment das runs out
+    if charge < das {
+        return 0;
+    }
+    (charge - das).as_nanos() / arr.as_nanos() + 1
+}
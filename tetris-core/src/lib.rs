@@ -0,0 +1,38 @@
+// game rules without any UI, so they can be used by the web app, tests, bots etc.
+mod board;
+mod bot;
+mod clock;
+mod finesse;
+mod game_mode;
+mod gravity;
+mod high_scores;
+mod input;
+mod lock_delay;
+mod piece;
+mod placements;
+mod randomizer;
+mod replay;
+mod rotation;
+mod rules;
+mod scoring;
+mod stats;
+
+pub use board::{Board, HoldRule, Square, GARBAGE_HUE, MAX_PREVIEW_LENGTH};
+pub use bot::{Bot, Plan, Weights};
+pub use clock::{format_seconds, format_time};
+pub use finesse::{Finesse, FinesseTarget, Press};
+pub use game_mode::{
+    Endless, FinesseTrainer, GameMode, Goal, Marathon, Ranking, Sprint, Ultra, GAME_MODES,
+};
+pub use gravity::{Gravity, GravityCurve, FRAMES_PER_SECOND, FRAME_DURATION, MAX_CATCH_UP_FRAMES};
+pub use high_scores::{FileStorage, HighScore, HighScores, ScoreStorage, MAX_HIGH_SCORES};
+pub use input::{Action, AutoShift, FrameInput, Handling};
+pub use lock_delay::{LockDelay, LockReset};
+pub use piece::{Direction, Orientation, Piece, PieceType};
+pub use placements::Placement;
+pub use randomizer::{Randomizer, SevenBag, Uniform};
+pub use replay::{Replay, MAX_REPLAY_FRAMES};
+pub use rotation::{Ars, Nintendo, Rotation, RotationSystem, Srs, SrsX, ROTATION_SYSTEMS};
+pub use rules::{build_board, param};
+pub use scoring::{GuidelineScoring, LineClear, NesScoring, Scoring, TSpin};
+pub use stats::Stats;
@@ -0,0 +1,25 @@
+use std::time::Duration;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LockReset {
+    Move, // every successful move or rotation restarts the timer, up to max_resets times
+    Step, // only moving down to a new lowest row restarts the timer
+}
+
+// how long a piece can lie on the stack before it locks
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LockDelay {
+    pub delay: Duration, // zero locks as soon as the piece can't fall any further
+    pub max_resets: u32,
+    pub reset: LockReset,
+}
+
+impl Default for LockDelay {
+    fn default() -> Self {
+        Self {
+            delay: Duration::from_millis(500),
+            max_resets: 15,
+            reset: LockReset::Move,
+        }
+    }
+}
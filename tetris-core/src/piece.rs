@@ -0,0 +1,222 @@
+use rand::{
+    distributions::{Distribution, Standard},
+    Rng,
+};
+
+use crate::rotation::{Rotation, RotationSystem};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Piece {
+    pub position: (i32, i32),
+    pub piece_type: PieceType, // color
+    pub orientation: Orientation,
+    pub rotation_system: &'static dyn RotationSystem,
+}
+
+impl Piece {
+    pub fn move_in_direction(&mut self, direction: Direction) {
+        match direction {
+            Direction::Up => self.position.1 += 1,
+            Direction::Down => self.position.1 -= 1,
+            Direction::Left => self.position.0 -= 1,
+            Direction::Right => self.position.0 += 1,
+        };
+    }
+
+    pub fn translate(&mut self, (dx, dy): (i32, i32)) {
+        self.position = (self.position.0 + dx, self.position.1 + dy);
+    }
+
+    pub fn rotate(&mut self, rotation: &Rotation) {
+        self.orientation = self.orientation.rotate(rotation);
+    }
+
+    pub fn squares(&self) -> Vec<(i32, i32)> {
+        self.rotation_system
+            .shape(&self.piece_type, &self.orientation)
+            .iter()
+            .map(|(dx, dy)| (self.position.0 + dx, self.position.1 + dy))
+            .collect()
+    }
+
+    pub fn squares_after_move(&self, direction: Direction) -> Vec<(i32, i32)> {
+        let (dx, dy) = match direction {
+            Direction::Up => (0, 1),
+            Direction::Down => (0, -1),
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+        };
+        self.squares()
+            .iter()
+            .map(|&(x, y)| (x + dx, y + dy))
+            .collect()
+    }
+
+    pub fn kicks(&self, rotation: &Rotation) -> Vec<(i32, i32)> {
+        self.rotation_system
+            .kicks(&self.piece_type, &self.orientation, rotation)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Orientation {
+    Deg0,
+    Deg90,
+    Deg180,
+    Deg270,
+}
+
+impl Orientation {
+    pub fn rotate_clockwise(&self) -> Self {
+        match self {
+            Self::Deg0 => Self::Deg90,
+            Self::Deg90 => Self::Deg180,
+            Self::Deg180 => Self::Deg270,
+            Self::Deg270 => Self::Deg0,
+        }
+    }
+
+    pub fn rotate_counterclockwise(&self) -> Self {
+        match self {
+            Self::Deg0 => Self::Deg270,
+            Self::Deg90 => Self::Deg0,
+            Self::Deg180 => Self::Deg90,
+            Self::Deg270 => Self::Deg180,
+        }
+    }
+
+    pub fn rotate(&self, rotation: &Rotation) -> Self {
+        match rotation {
+            Rotation::Clockwise => self.rotate_clockwise(),
+            Rotation::CounterClockwise => self.rotate_counterclockwise(),
+            Rotation::Half => self.rotate_180(),
+        }
+    }
+
+    pub fn degrees(&self) -> u32 {
+        match self {
+            Self::Deg0 => 0,
+            Self::Deg90 => 90,
+            Self::Deg180 => 180,
+            Self::Deg270 => 270,
+        }
+    }
+
+    pub fn from_degrees(degrees: u32) -> Option<Self> {
+        match degrees {
+            0 => Some(Self::Deg0),
+            90 => Some(Self::Deg90),
+            180 => Some(Self::Deg180),
+            270 => Some(Self::Deg270),
+            _ => None,
+        }
+    }
+
+    pub fn rotate_180(&self) -> Self {
+        match self {
+            Self::Deg0 => Self::Deg180,
+            Self::Deg90 => Self::Deg270,
+            Self::Deg180 => Self::Deg0,
+            Self::Deg270 => Self::Deg90,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PieceType {
+    I,
+    O,
+    T,
+    J,
+    L,
+    S,
+    Z,
+}
+
+impl PieceType {
+    pub const ALL: [PieceType; 7] = [
+        PieceType::I,
+        PieceType::O,
+        PieceType::T,
+        PieceType::J,
+        PieceType::L,
+        PieceType::S,
+        PieceType::Z,
+    ];
+
+    pub fn letter(&self) -> char {
+        match self {
+            PieceType::I => 'I',
+            PieceType::O => 'O',
+            PieceType::T => 'T',
+            PieceType::J => 'J',
+            PieceType::L => 'L',
+            PieceType::S => 'S',
+            PieceType::Z => 'Z',
+        }
+    }
+
+    pub fn from_letter(letter: char) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|piece_type| piece_type.letter() == letter)
+    }
+
+    pub fn to_squares(&self) -> Vec<(i32, i32)> {
+        match self {
+            PieceType::I => vec![(-1, 0), (0, 0), (1, 0), (2, 0)],
+            PieceType::O => vec![(0, 0), (1, 0), (0, -1), (1, -1)],
+            PieceType::T => vec![(0, 0), (1, 0), (-1, 0), (0, 1)],
+            PieceType::J => vec![(0, 0), (-1, 0), (1, 0), (-1, -1)],
+            PieceType::L => vec![(0, 0), (-1, 0), (1, 0), (1, -1)],
+            PieceType::S => vec![(0, 0), (-1, 0), (0, -1), (1, -1)],
+            PieceType::Z => vec![(0, 0), (1, 0), (0, -1), (-1, -1)],
+        }
+    }
+
+    pub fn average_pos(&self) -> (f32, f32) {
+        let mut sum_x = 0.;
+        let mut sum_y = 0.;
+        for (x, y) in self.to_squares() {
+            sum_x += x as f32;
+            sum_y += y as f32;
+        }
+        (sum_x / 4., sum_y / 4.)
+    }
+
+    pub fn to_hue(&self) -> f32 {
+        match self {
+            PieceType::I => 303.,
+            PieceType::O => 59.,
+            PieceType::T => 28.,
+            PieceType::J => 128.,
+            PieceType::L => 245.,
+            PieceType::S => 183.,
+            PieceType::Z => 0.,
+        }
+    }
+}
+
+impl Distribution<PieceType> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> PieceType {
+        let index: u8 = rng.gen_range(0..7);
+        match index {
+            0 => PieceType::I,
+            1 => PieceType::O,
+            2 => PieceType::T,
+            3 => PieceType::J,
+            4 => PieceType::L,
+            5 => PieceType::S,
+            6 => PieceType::Z,
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
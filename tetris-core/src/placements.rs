@@ -0,0 +1,101 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::board::Board;
+use crate::input::Action;
+use crate::piece::{Direction, Piece};
+use crate::rotation::Rotation;
+
+// somewhere a piece can lock, and the fewest key presses that get it there from where it started
+#[derive(Clone, Debug, PartialEq)]
+pub struct Placement {
+    pub piece: Piece,        // where it locks
+    pub inputs: Vec<Action>, // one press each, soft drop goes down one row, ends with the hard drop
+}
+
+// in the order they're tried, so a tie goes to moving before rotating before soft dropping
+const MOVES: [Action; 6] = [
+    Action::MoveLeft,
+    Action::MoveRight,
+    Action::RotateCW,
+    Action::RotateCCW,
+    Action::Rotate180,
+    Action::SoftDrop,
+];
+
+impl Board {
+    // every distinct final placement of the piece, with the same moves, kicks and soft drops the
+    // player has, so tucks and spins are in there too. Shortest first, pieces covering the same
+    // squares count once
+    pub fn placements(&self, piece: &Piece) -> Vec<Placement> {
+        let mut placements = Vec::new();
+        if !self.check_valid_piece_position(piece) {
+            return placements;
+        }
+        let mut seen = HashSet::from([key(piece)]);
+        let mut landings = HashMap::new();
+        let mut landed = HashSet::new();
+        let mut queue = VecDeque::from([(piece.clone(), Vec::new())]);
+        // breadth first, so the first way to a node or landing is a shortest one
+        while let Some((piece, inputs)) = queue.pop_front() {
+            let dropped = self.landing(&piece, &mut landings);
+            let mut squares = dropped.squares();
+            squares.sort();
+            if landed.insert(squares) {
+                let mut inputs = inputs.clone();
+                inputs.push(Action::HardDrop);
+                placements.push(Placement {
+                    piece: dropped,
+                    inputs,
+                });
+            }
+
+            for action in MOVES {
+                let next = match action {
+                    Action::RotateCW => self.rotated(&piece, &Rotation::Clockwise),
+                    Action::RotateCCW => self.rotated(&piece, &Rotation::CounterClockwise),
+                    Action::Rotate180 => self.rotated(&piece, &Rotation::Half),
+                    _ => action
+                        .direction()
+                        .and_then(|direction| self.moved(&piece, direction))
+                        .map(|piece| (piece, 0)),
+                };
+                let Some((next, _kick)) = next else {
+                    continue;
+                };
+                if seen.insert(key(&next)) {
+                    let mut inputs = inputs.clone();
+                    inputs.push(action);
+                    queue.push_back((next, inputs));
+                }
+            }
+        }
+        placements
+    }
+
+    // same as dropped, but remembers every row on the way down so the search doesn't keep redoing it
+    fn landing(&self, piece: &Piece, landings: &mut HashMap<Key, (i32, i32)>) -> Piece {
+        let mut path = Vec::new();
+        let mut landing = piece.clone();
+        loop {
+            if let Some(&position) = landings.get(&key(&landing)) {
+                landing.position = position;
+                break;
+            }
+            path.push(key(&landing));
+            match self.moved(&landing, Direction::Down) {
+                Some(lower) => landing = lower,
+                None => break,
+            }
+        }
+        for key in path {
+            landings.insert(key, landing.position);
+        }
+        landing
+    }
+}
+
+type Key = ((i32, i32), u32);
+
+fn key(piece: &Piece) -> Key {
+    (piece.position, piece.orientation.degrees())
+}
@@ -0,0 +1,46 @@
+use rand::{seq::SliceRandom, Rng, RngCore};
+
+use crate::piece::PieceType;
+
+// decides which pieces come next. Gets the rng passed in, so the board is in charge of randomness
+pub trait Randomizer {
+    fn next_piece(&mut self, rng: &mut dyn RngCore) -> PieceType;
+
+    // forget any state from the previous game
+    fn reset(&mut self) {}
+}
+
+// every piece equally likely every time, so droughts and floods are possible
+pub struct Uniform;
+
+impl Randomizer for Uniform {
+    fn next_piece(&mut self, rng: &mut dyn RngCore) -> PieceType {
+        rng.gen()
+    }
+}
+
+// deals all seven pieces in a random order, then starts over with a fresh bag
+#[derive(Default)]
+pub struct SevenBag {
+    bag: Vec<PieceType>,
+}
+
+impl SevenBag {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Randomizer for SevenBag {
+    fn next_piece(&mut self, rng: &mut dyn RngCore) -> PieceType {
+        if self.bag.is_empty() {
+            self.bag = PieceType::ALL.to_vec();
+            self.bag.shuffle(rng);
+        }
+        self.bag.pop().unwrap() // bag was just refilled, so never empty here
+    }
+
+    fn reset(&mut self) {
+        self.bag.clear();
+    }
+}
@@ -0,0 +1,164 @@
+use std::{fmt, str::FromStr, time::Duration};
+
+use crate::gravity::{FRAMES_PER_SECOND, FRAME_DURATION};
+use crate::input::{Action, FrameInput, Handling};
+
+// the input of every frame. Stepping a board with the same seed and rules through these gives
+// exactly the same game
+#[derive(Clone, Debug, PartialEq)]
+pub struct Replay {
+    pub seed: u64,
+    pub game_mode: String,
+    pub rules: String, // whatever else the app needs to set the board up the same way, e.g. url parameters
+    pub handling: Handling,
+    pub frames: Vec<FrameInput>,
+}
+
+impl Replay {
+    pub fn new(seed: u64, game_mode: &str, rules: &str, handling: Handling) -> Self {
+        Self {
+            seed,
+            game_mode: game_mode.to_string(),
+            rules: rules.to_string(),
+            handling,
+            frames: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, input: FrameInput) {
+        self.frames.push(input);
+    }
+
+    pub fn duration(&self) -> Duration {
+        FRAME_DURATION * self.frames.len() as u32
+    }
+}
+
+const HEADER: &str = "tetris replay 2";
+
+// three hours, longer than any game. Keeps a bad or hostile file from filling memory with x<count> runs
+pub const MAX_REPLAY_FRAMES: usize = 3 * 60 * 60 * FRAMES_PER_SECOND as usize;
+
+fn action_index(action: &Action) -> usize {
+    Action::ALL
+        .iter()
+        .position(|other| other == action)
+        .unwrap() // ALL has every action
+}
+
+fn format_actions(actions: &[Action]) -> String {
+    actions
+        .iter()
+        .map(|action| action_index(action).to_string())
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn parse_actions(actions: &str) -> Result<Vec<Action>, String> {
+    if actions.is_empty() {
+        return Ok(Vec::new());
+    }
+    actions
+        .split('.')
+        .map(|index| {
+            index
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| Action::ALL.get(index).copied())
+                .ok_or(format!("bad action {index}"))
+        })
+        .collect()
+}
+
+// plain text, one setting per line and then all frames on one line as pressed/held action numbers,
+// e.g. "0/0" presses and holds move left. Runs of the same frame get x<count>, so "/x300" is five idle seconds
+impl fmt::Display for Replay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{HEADER}")?;
+        writeln!(f, "seed {}", self.seed)?;
+        writeln!(f, "mode {}", self.game_mode)?;
+        writeln!(f, "rules {}", self.rules)?;
+        writeln!(
+            f,
+            "handling {},{},{}",
+            self.handling.das.as_micros(),
+            self.handling.arr.as_micros(),
+            self.handling.soft_drop_factor
+        )?;
+        let mut tokens = Vec::new();
+        let mut frames = self.frames.iter().peekable();
+        while let Some(frame) = frames.next() {
+            let mut count = 1;
+            while frames.next_if_eq(&frame).is_some() {
+                count += 1;
+            }
+            let token = format!(
+                "{}/{}",
+                format_actions(&frame.pressed),
+                format_actions(&frame.held)
+            );
+            if count == 1 {
+                tokens.push(token);
+            } else {
+                tokens.push(format!("{token}x{count}"));
+            }
+        }
+        writeln!(f, "frames {}", tokens.join(" "))
+    }
+}
+
+impl FromStr for Replay {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines();
+        if lines.next().map(str::trim) != Some(HEADER) {
+            return Err("not a replay file, or from another version".to_string());
+        }
+        let mut field = |name: &str| {
+            lines
+                .next()
+                .and_then(|line| line.strip_prefix(name))
+                .and_then(|value| value.strip_prefix(' '))
+                .map(|value| value.trim().to_string())
+                .ok_or(format!("missing {name}"))
+        };
+        let seed = field("seed")?.parse().map_err(|_| "bad seed")?;
+        let game_mode = field("mode")?;
+        let rules = field("rules")?;
+        let handling = field("handling")?;
+        let frames = field("frames")?;
+
+        let handling_values: Vec<u64> = handling
+            .split(',')
+            .map(|value| value.parse().ok())
+            .collect::<Option<_>>()
+            .ok_or("bad handling")?;
+        let [das, arr, soft_drop_factor] = handling_values[..] else {
+            return Err("bad handling".to_string());
+        };
+        let handling = Handling {
+            das: Duration::from_micros(das),
+            arr: Duration::from_micros(arr),
+            soft_drop_factor: soft_drop_factor as u32,
+        };
+
+        let mut replay = Replay::new(seed, &game_mode, &rules, handling);
+        for token in frames.split_whitespace() {
+            let (frame, count) = token.split_once('x').unwrap_or((token, "1"));
+            let count: usize = count.parse().map_err(|_| format!("bad frame {token}"))?;
+            if count > MAX_REPLAY_FRAMES - replay.frames.len() {
+                return Err(format!("more than {MAX_REPLAY_FRAMES} frames"));
+            }
+            let (pressed, held) = frame.split_once('/').ok_or(format!("bad frame {token}"))?;
+            let input = FrameInput {
+                pressed: parse_actions(pressed)?,
+                held: parse_actions(held)?,
+            };
+            for _ in 0..count {
+                replay.record(input.clone());
+            }
+        }
+        Ok(replay)
+    }
+}
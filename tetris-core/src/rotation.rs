@@ -0,0 +1,305 @@
+use std::fmt;
+
+use crate::piece::{Orientation, PieceType};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Rotation {
+    Clockwise,
+    CounterClockwise,
+    Half,
+}
+
+// decides what the pieces look like in every orientation and where they go when a rotation is blocked.
+// Note that PieceType::to_squares has J/L and S/Z the other way round compared to the guideline,
+// the shapes below follow the game's own pieces so they look the same in every system
+pub trait RotationSystem {
+    fn name(&self) -> &'static str;
+
+    // squares relative to the piece position
+    fn shape(&self, piece_type: &PieceType, orientation: &Orientation) -> Vec<(i32, i32)>;
+
+    // offsets to try in order, the first one that fits wins
+    fn kicks(
+        &self,
+        piece_type: &PieceType,
+        from: &Orientation,
+        rotation: &Rotation,
+    ) -> Vec<(i32, i32)>;
+}
+
+impl fmt::Debug for dyn RotationSystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl PartialEq for dyn RotationSystem {
+    fn eq(&self, other: &Self) -> bool {
+        self.name() == other.name()
+    }
+}
+
+pub const ROTATION_SYSTEMS: [&dyn RotationSystem; 4] = [&Srs, &SrsX, &Ars, &Nintendo];
+
+fn rotate_square((dx, dy): (i32, i32), orientation: &Orientation) -> (i32, i32) {
+    match orientation {
+        Orientation::Deg0 => (dx, dy),
+        Orientation::Deg90 => (dy, -dx),
+        Orientation::Deg180 => (-dx, -dy),
+        Orientation::Deg270 => (-dy, dx),
+    }
+}
+
+// Super Rotation System, with SRS+ (TETR.IO) kicks for 180 degree turns
+pub struct Srs;
+
+impl RotationSystem for Srs {
+    fn name(&self) -> &'static str {
+        "SRS"
+    }
+
+    fn shape(&self, piece_type: &PieceType, orientation: &Orientation) -> Vec<(i32, i32)> {
+        if *piece_type == PieceType::O {
+            return piece_type.to_squares(); // O doesn't move when rotating
+        }
+        piece_type
+            .to_squares()
+            .into_iter()
+            .map(|square| rotate_square(square, orientation))
+            .collect()
+    }
+
+    fn kicks(
+        &self,
+        piece_type: &PieceType,
+        from: &Orientation,
+        rotation: &Rotation,
+    ) -> Vec<(i32, i32)> {
+        use Orientation as Or;
+        use PieceType as PT;
+        use Rotation as R;
+        match (piece_type, from, rotation) {
+            (PT::O, _, _) => vec![(0, 0)],
+
+            (_, Or::Deg0, R::Half) => vec![(0, 0), (0, 1), (1, 1), (-1, 1), (1, 0), (-1, 0)],
+            (_, Or::Deg90, R::Half) => vec![(0, 0), (1, 0), (1, 2), (1, 1), (0, 2), (0, 1)],
+            (_, Or::Deg180, R::Half) => vec![(0, 0), (0, -1), (-1, -1), (1, -1), (-1, 0), (1, 0)],
+            (_, Or::Deg270, R::Half) => vec![(0, 0), (-1, 0), (-1, 2), (-1, 1), (0, 2), (0, 1)],
+
+            (PT::I, Or::Deg0, R::Clockwise) => vec![(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)],
+            (PT::I, Or::Deg90, R::CounterClockwise) => {
+                vec![(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)]
+            }
+            (PT::I, Or::Deg90, R::Clockwise) => vec![(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)],
+            (PT::I, Or::Deg180, R::CounterClockwise) => {
+                vec![(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)]
+            }
+            (PT::I, Or::Deg180, R::Clockwise) => vec![(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)],
+            (PT::I, Or::Deg270, R::CounterClockwise) => {
+                vec![(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)]
+            }
+            (PT::I, Or::Deg270, R::Clockwise) => vec![(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)],
+            (PT::I, Or::Deg0, R::CounterClockwise) => {
+                vec![(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)]
+            }
+
+            // J, L, T, S and Z
+            (_, Or::Deg0, R::Clockwise) => vec![(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)],
+            (_, Or::Deg90, R::CounterClockwise) => vec![(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],
+            (_, Or::Deg90, R::Clockwise) => vec![(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],
+            (_, Or::Deg180, R::CounterClockwise) => {
+                vec![(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)]
+            }
+            (_, Or::Deg180, R::Clockwise) => vec![(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],
+            (_, Or::Deg270, R::CounterClockwise) => {
+                vec![(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)]
+            }
+            (_, Or::Deg270, R::Clockwise) => vec![(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],
+            (_, Or::Deg0, R::CounterClockwise) => vec![(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],
+        }
+    }
+}
+
+// SRS with the big 180 degree kick table from Heboris
+pub struct SrsX;
+
+impl RotationSystem for SrsX {
+    fn name(&self) -> &'static str {
+        "SRS-X"
+    }
+
+    fn shape(&self, piece_type: &PieceType, orientation: &Orientation) -> Vec<(i32, i32)> {
+        Srs.shape(piece_type, orientation)
+    }
+
+    fn kicks(
+        &self,
+        piece_type: &PieceType,
+        from: &Orientation,
+        rotation: &Rotation,
+    ) -> Vec<(i32, i32)> {
+        use Orientation as Or;
+        match (piece_type, from, rotation) {
+            (PieceType::O, _, _) => vec![(0, 0)],
+            (_, Or::Deg0, Rotation::Half) => vec![
+                (0, 0),
+                (1, 0),
+                (2, 0),
+                (1, 1),
+                (2, 1),
+                (-1, 0),
+                (-2, 0),
+                (-1, 1),
+                (-2, 1),
+                (0, -1),
+                (3, 0),
+                (-3, 0),
+            ],
+            (_, Or::Deg90, Rotation::Half) => vec![
+                (0, 0),
+                (0, 1),
+                (0, 2),
+                (-1, 1),
+                (-1, 2),
+                (0, -1),
+                (0, -2),
+                (-1, -1),
+                (-1, -2),
+                (1, 0),
+                (0, 3),
+                (0, -3),
+            ],
+            (_, Or::Deg180, Rotation::Half) => vec![
+                (0, 0),
+                (-1, 0),
+                (-2, 0),
+                (-1, -1),
+                (-2, -1),
+                (1, 0),
+                (2, 0),
+                (1, -1),
+                (2, -1),
+                (0, 1),
+                (-3, 0),
+                (3, 0),
+            ],
+            (_, Or::Deg270, Rotation::Half) => vec![
+                (0, 0),
+                (0, 1),
+                (0, 2),
+                (1, 1),
+                (1, 2),
+                (0, -1),
+                (0, -2),
+                (1, -1),
+                (1, -2),
+                (-1, 0),
+                (0, 3),
+                (0, -3),
+            ],
+            _ => Srs.kicks(piece_type, from, rotation),
+        }
+    }
+}
+
+// Arika Rotation System (TGM). Pieces sit at the bottom of their box, T spawns pointing down.
+// Kicks are one step right then left, I never kicks. The centre column rule isn't implemented
+pub struct Ars;
+
+impl RotationSystem for Ars {
+    fn name(&self) -> &'static str {
+        "ARS"
+    }
+
+    fn shape(&self, piece_type: &PieceType, orientation: &Orientation) -> Vec<(i32, i32)> {
+        use Orientation as Or;
+        use PieceType as PT;
+        match (piece_type, orientation) {
+            (PT::O, _) => vec![(0, 0), (1, 0), (0, -1), (1, -1)],
+
+            (PT::I, Or::Deg0 | Or::Deg180) => vec![(-1, 0), (0, 0), (1, 0), (2, 0)],
+            (PT::I, Or::Deg90 | Or::Deg270) => vec![(1, 1), (1, 0), (1, -1), (1, -2)],
+
+            (PT::T, Or::Deg0) => vec![(-1, 0), (0, 0), (1, 0), (0, -1)],
+            (PT::T, Or::Deg90) => vec![(0, 1), (-1, 0), (0, 0), (0, -1)],
+            (PT::T, Or::Deg180) => vec![(0, 0), (-1, -1), (0, -1), (1, -1)],
+            (PT::T, Or::Deg270) => vec![(0, 1), (0, 0), (1, 0), (0, -1)],
+
+            (PT::J, Or::Deg0) => vec![(-1, 0), (0, 0), (1, 0), (-1, -1)],
+            (PT::J, Or::Deg90) => vec![(-1, 1), (0, 1), (0, 0), (0, -1)],
+            (PT::J, Or::Deg180) => vec![(1, 0), (-1, -1), (0, -1), (1, -1)],
+            (PT::J, Or::Deg270) => vec![(0, 1), (0, 0), (0, -1), (1, -1)],
+
+            (PT::L, Or::Deg0) => vec![(-1, 0), (0, 0), (1, 0), (1, -1)],
+            (PT::L, Or::Deg90) => vec![(0, 1), (0, 0), (-1, -1), (0, -1)],
+            (PT::L, Or::Deg180) => vec![(-1, 0), (-1, -1), (0, -1), (1, -1)],
+            (PT::L, Or::Deg270) => vec![(0, 1), (1, 1), (0, 0), (0, -1)],
+
+            (PT::S, Or::Deg0 | Or::Deg180) => vec![(-1, 0), (0, 0), (0, -1), (1, -1)],
+            (PT::S, Or::Deg90 | Or::Deg270) => vec![(1, 1), (0, 0), (1, 0), (0, -1)],
+
+            (PT::Z, Or::Deg0 | Or::Deg180) => vec![(0, 0), (1, 0), (-1, -1), (0, -1)],
+            (PT::Z, Or::Deg90 | Or::Deg270) => vec![(-1, 1), (-1, 0), (0, 0), (0, -1)],
+        }
+    }
+
+    fn kicks(
+        &self,
+        piece_type: &PieceType,
+        _from: &Orientation,
+        _rotation: &Rotation,
+    ) -> Vec<(i32, i32)> {
+        match piece_type {
+            PieceType::I | PieceType::O => vec![(0, 0)],
+            _ => vec![(0, 0), (1, 0), (-1, 0)],
+        }
+    }
+}
+
+// classic NES rotation: turns around the middle square and never kicks
+pub struct Nintendo;
+
+impl RotationSystem for Nintendo {
+    fn name(&self) -> &'static str {
+        "Nintendo"
+    }
+
+    fn shape(&self, piece_type: &PieceType, orientation: &Orientation) -> Vec<(i32, i32)> {
+        use Orientation as Or;
+        use PieceType as PT;
+        let spawn_shape = match piece_type {
+            PT::O => return vec![(0, 0), (1, 0), (0, -1), (1, -1)],
+
+            // these only have two orientations
+            PT::I => match orientation {
+                Or::Deg0 | Or::Deg180 => return vec![(-1, 0), (0, 0), (1, 0), (2, 0)],
+                Or::Deg90 | Or::Deg270 => return vec![(1, 2), (1, 1), (1, 0), (1, -1)],
+            },
+            PT::S => match orientation {
+                Or::Deg0 | Or::Deg180 => return vec![(-1, 0), (0, 0), (0, -1), (1, -1)],
+                Or::Deg90 | Or::Deg270 => return vec![(1, 1), (1, 0), (0, 0), (0, -1)],
+            },
+            PT::Z => match orientation {
+                Or::Deg0 | Or::Deg180 => return vec![(0, 0), (1, 0), (-1, -1), (0, -1)],
+                Or::Deg90 | Or::Deg270 => return vec![(0, 1), (0, 0), (1, 0), (1, -1)],
+            },
+
+            PT::T => vec![(-1, 0), (0, 0), (1, 0), (0, -1)],
+            PT::J => vec![(-1, 0), (0, 0), (1, 0), (-1, -1)],
+            PT::L => vec![(-1, 0), (0, 0), (1, 0), (1, -1)],
+        };
+        spawn_shape
+            .into_iter()
+            .map(|square| rotate_square(square, orientation))
+            .collect()
+    }
+
+    fn kicks(
+        &self,
+        _piece_type: &PieceType,
+        _from: &Orientation,
+        _rotation: &Rotation,
+    ) -> Vec<(i32, i32)> {
+        vec![(0, 0)]
+    }
+}
@@ -0,0 +1,63 @@
+use std::{str::FromStr, time::Duration};
+
+use crate::board::{Board, HoldRule};
+use crate::game_mode::GameMode;
+use crate::gravity::GravityCurve;
+use crate::lock_delay::{LockDelay, LockReset};
+use crate::randomizer::{Randomizer, SevenBag, Uniform};
+use crate::rotation::{Srs, ROTATION_SYSTEMS};
+use crate::scoring::NesScoring;
+
+// looks up name in rules written like url parameters, "rotation=ars&hold=swap" (a leading '?' is fine)
+pub fn param<T: FromStr>(rules: &str, name: &str) -> Option<T> {
+    rules
+        .trim_start_matches('?')
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .and_then(|(_, value)| value.parse().ok())
+}
+
+// every front end sets up its board from the same rules string, replays keep it so they can do it again
+pub fn build_board(rules: &str, seed: u64, game_mode: &'static dyn GameMode) -> Board {
+    let rotation_system = param::<String>(rules, "rotation")
+        .and_then(|name| {
+            ROTATION_SYSTEMS
+                .into_iter()
+                .find(|system| system.name().eq_ignore_ascii_case(&name))
+        })
+        .unwrap_or(&Srs);
+    let randomizer: Box<dyn Randomizer> = match param::<String>(rules, "randomizer").as_deref() {
+        Some("uniform") => Box::new(Uniform),
+        _ => Box::new(SevenBag::new()),
+    };
+    let mut board = Board::with_rules(10, 20, seed, randomizer, rotation_system)
+        .expect("every piece fits on 10x20");
+    if let Some(preview_length) = param(rules, "next") {
+        board.set_preview_length(preview_length);
+    }
+    if param::<String>(rules, "hold").as_deref() == Some("swap") {
+        board.set_hold_rule(HoldRule::Swap);
+    }
+    let mut lock_delay = LockDelay::default();
+    if let Some(lock_ms) = param(rules, "lock") {
+        lock_delay.delay = Duration::from_millis(lock_ms);
+    }
+    if param::<String>(rules, "lockreset").as_deref() == Some("step") {
+        lock_delay.reset = LockReset::Step;
+    }
+    board.set_lock_delay(lock_delay);
+    let nes_scoring = param::<String>(rules, "scoring").as_deref() == Some("nes");
+    // nes scoring pays by nes levels, counted from 0, and only the nes curve has those
+    if nes_scoring || param::<String>(rules, "gravity").as_deref() == Some("nes") {
+        board.set_gravity_curve(GravityCurve::Nes);
+    }
+    if let Some(start_level) = param(rules, "level") {
+        board.set_start_level(start_level);
+    }
+    if nes_scoring {
+        board.set_scoring(Box::new(NesScoring));
+    }
+    board.set_game_mode(game_mode);
+    board
+}
@@ -0,0 +1,111 @@
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TSpin {
+    None,
+    Mini,
+    Full,
+}
+
+// what happened when a piece locked and cleared lines (or did a t-spin without clearing any)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LineClear {
+    pub lines: u32,
+    pub t_spin: TSpin,
+    pub back_to_back: bool, // difficult clear right after another difficult clear
+    pub combo: u32,         // 0 for the first clear in a row, 1 for the second etc.
+    pub piece_number: u32, // which locked piece caused this, to tell apart clears that look the same
+}
+
+impl LineClear {
+    // difficult clears keep back-to-back going, other line clears break it
+    pub fn is_difficult(&self) -> bool {
+        self.lines == 4 || (self.lines > 0 && self.t_spin != TSpin::None)
+    }
+
+    // e.g. "T-SPIN DOUBLE" or "TETRIS"
+    pub fn name(&self) -> String {
+        let lines = match self.lines {
+            0 => "",
+            1 => "SINGLE",
+            2 => "DOUBLE",
+            3 => "TRIPLE",
+            _ => "TETRIS",
+        };
+        let t_spin = match self.t_spin {
+            TSpin::None => "",
+            TSpin::Mini => "T-SPIN MINI",
+            TSpin::Full => "T-SPIN",
+        };
+        [t_spin, lines]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+// decides how many points things are worth. The board keeps track of combos and back-to-backs
+pub trait Scoring {
+    fn line_clear(&self, clear: &LineClear, level: u32) -> u32;
+    fn soft_drop(&self, cells: u32) -> u32;
+    fn hard_drop(&self, cells: u32) -> u32;
+}
+
+pub struct GuidelineScoring;
+
+impl Scoring for GuidelineScoring {
+    fn line_clear(&self, clear: &LineClear, level: u32) -> u32 {
+        let base = match (&clear.t_spin, clear.lines) {
+            (TSpin::None, 0) => 0,
+            (TSpin::None, 1) => 100,
+            (TSpin::None, 2) => 300,
+            (TSpin::None, 3) => 500,
+            (TSpin::None, _) => 800,
+            (TSpin::Mini, 0) => 100,
+            (TSpin::Mini, 1) => 200,
+            (TSpin::Mini, _) => 400,
+            (TSpin::Full, 0) => 400,
+            (TSpin::Full, 1) => 800,
+            (TSpin::Full, 2) => 1200,
+            (TSpin::Full, _) => 1600,
+        };
+        let base = if clear.back_to_back {
+            base * 3 / 2
+        } else {
+            base
+        };
+        let combo_bonus = if clear.lines > 0 { 50 * clear.combo } else { 0 };
+        (base + combo_bonus) * level.max(1)
+    }
+
+    fn soft_drop(&self, cells: u32) -> u32 {
+        cells
+    }
+
+    fn hard_drop(&self, cells: u32) -> u32 {
+        2 * cells
+    }
+}
+
+// no t-spins, back-to-back, combos or hard drop points
+pub struct NesScoring;
+
+impl Scoring for NesScoring {
+    fn line_clear(&self, clear: &LineClear, level: u32) -> u32 {
+        let base = match clear.lines {
+            0 => 0,
+            1 => 40,
+            2 => 100,
+            3 => 300,
+            _ => 1200,
+        };
+        base * (level + 1)
+    }
+
+    fn soft_drop(&self, cells: u32) -> u32 {
+        cells
+    }
+
+    fn hard_drop(&self, _cells: u32) -> u32 {
+        0
+    }
+}
@@ -0,0 +1,89 @@
+use std::time::Duration;
+
+use crate::piece::PieceType;
+use crate::scoring::{LineClear, TSpin};
+
+// garbage sent for 0, 1, 2... combo in a versus game, the last one carries on from there
+const COMBO_ATTACK: [u32; 12] = [0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 4, 5];
+
+// what happened over a game, kept up to date by the board
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub pieces: u32,
+    pub piece_types: [u32; 7], // in PieceType::ALL order
+    pub singles: u32,          // t-spins are counted apart from these
+    pub doubles: u32,
+    pub triples: u32,
+    pub tetrises: u32,
+    pub t_spins: [u32; 4], // by lines cleared, minis included
+    pub attack: u32,       // garbage lines the clears would have sent in a versus game
+    pub keys: u32,         // every press that got to the board, pause and restart aside
+    pub holds: u32,
+    pub time: Duration, // not counting pauses
+}
+
+impl Stats {
+    pub fn add_piece(&mut self, piece_type: &PieceType) {
+        self.pieces += 1;
+        if let Some(index) = PieceType::ALL.iter().position(|t| t == piece_type) {
+            self.piece_types[index] += 1;
+        }
+    }
+
+    pub fn add_clear(&mut self, clear: &LineClear) {
+        match (&clear.t_spin, clear.lines) {
+            (TSpin::None, 0) => {}
+            (TSpin::None, 1) => self.singles += 1,
+            (TSpin::None, 2) => self.doubles += 1,
+            (TSpin::None, 3) => self.triples += 1,
+            (TSpin::None, _) => self.tetrises += 1,
+            (_, lines) => self.t_spins[(lines as usize).min(3)] += 1,
+        }
+        self.attack += attack(clear);
+    }
+
+    pub fn pieces_of(&self, piece_type: &PieceType) -> u32 {
+        PieceType::ALL
+            .iter()
+            .position(|t| t == piece_type)
+            .map_or(0, |index| self.piece_types[index])
+    }
+
+    // pieces per second
+    pub fn pps(&self) -> f32 {
+        per(self.pieces, self.time.as_secs_f32())
+    }
+
+    // attack per minute
+    pub fn apm(&self) -> f32 {
+        per(self.attack, self.time.as_secs_f32() / 60.)
+    }
+
+    // keys per piece
+    pub fn kpp(&self) -> f32 {
+        per(self.keys, self.pieces as f32)
+    }
+}
+
+// 0 rather than NaN or infinity before anything's happened
+fn per(count: u32, amount: f32) -> f32 {
+    if amount > 0. {
+        count as f32 / amount
+    } else {
+        0.
+    }
+}
+
+// guideline garbage table, as in most versus games
+fn attack(clear: &LineClear) -> u32 {
+    if clear.lines == 0 {
+        return 0;
+    }
+    let base = match (&clear.t_spin, clear.lines) {
+        (TSpin::None, lines) => [0, 1, 2, 4][(lines as usize).min(4) - 1],
+        (TSpin::Mini, lines) => lines - 1,
+        (TSpin::Full, lines) => 2 * lines,
+    };
+    let combo = COMBO_ATTACK[(clear.combo as usize).min(COMBO_ATTACK.len() - 1)];
+    base + clear.back_to_back as u32 + combo
+}
@@ -0,0 +1,72 @@
+mod common;
+
+use common::tap;
+use tetris_core::{Action, Board, Bot, Orientation, PieceType};
+
+#[test]
+fn takes_the_tetris() {
+    let mut board = common::board(
+        PieceType::I,
+        Orientation::Deg0,
+        "
+        next OOOOOO
+        |          |
+        |    ++++  |
+        |          |
+        |          |
+        |          |
+        |          |
+        |*** ******|
+        |*** ******|
+        |*** ******|
+        |*** ******|",
+    );
+    let plan = Bot::default().plan(&board).unwrap();
+    assert_eq!(plan.piece.orientation.degrees() % 180, 90);
+    assert_eq!(plan.inputs.last(), Some(&Action::HardDrop));
+    tap(&mut board, &plan.inputs);
+    assert_eq!(board.lines_cleared(), 4);
+}
+
+#[test]
+fn holds_for_a_better_piece() {
+    let board = common::board(
+        PieceType::S,
+        Orientation::Deg0,
+        "
+        hold I
+        |          |
+        |    ++    |
+        |     ++   |
+        |          |
+        |          |
+        |          |
+        |********* |
+        |********* |
+        |********* |
+        |********* |",
+    );
+    let plan = Bot::default().plan(&board).unwrap();
+    assert_eq!(plan.inputs.first(), Some(&Action::Hold));
+    assert_eq!(plan.piece.piece_type, PieceType::I);
+    assert_eq!(
+        plan.piece
+            .squares()
+            .iter()
+            .filter(|&&(x, _)| x == 9)
+            .count(),
+        4
+    );
+}
+
+#[test]
+fn keeps_a_game_going() {
+    let mut board = Board::with_seed(10, 20, 5).unwrap();
+    let bot = Bot::default();
+    for _ in 0..100 {
+        let plan = bot.plan(&board).unwrap();
+        tap(&mut board, &plan.inputs);
+    }
+    assert!(!board.done());
+    assert!(board.lines_cleared() >= 35, "{}", board.lines_cleared());
+}
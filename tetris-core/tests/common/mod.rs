@@ -0,0 +1,77 @@
+// boards from the same ascii art Board's Display prints:
+// '*' is a locked square, '+' the active piece, border lines are optional
+#![allow(dead_code)] // every test file uses a different part of this
+use tetris_core::{Action, Board, FrameInput, Orientation, Piece, PieceType, Srs};
+
+pub const SEED: u64 = 1;
+
+fn rows(fixture: &str) -> Vec<&str> {
+    fixture
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('+'))
+        .map(|line| line.trim_start_matches('|').trim_end_matches('|'))
+        .collect()
+}
+
+// the '+' squares have to be piece_type in this orientation
+pub fn board(piece_type: PieceType, orientation: Orientation, fixture: &str) -> Board {
+    let board = parse(&format!("orientation {}\n{fixture}", orientation.degrees()));
+    assert_eq!(board.active_piece().piece_type, piece_type);
+    board
+}
+
+// just the locked squares, the active piece is whatever the randomizer deals first
+pub fn stack(fixture: &str) -> Board {
+    assert!(
+        !fixture.contains('+'),
+        "use board() for fixtures with a piece"
+    );
+    parse(fixture)
+}
+
+fn parse(fixture: &str) -> Board {
+    format!("seed {SEED}\n{fixture}").parse().unwrap()
+}
+
+pub fn piece(piece_type: PieceType, orientation: Orientation, position: (i32, i32)) -> Piece {
+    Piece {
+        position,
+        piece_type,
+        orientation,
+        rotation_system: &Srs,
+    }
+}
+
+// presses all of these in one frame, like the bot does. Nothing is held so the board doesn't auto repeat
+pub fn tap(board: &mut Board, inputs: &[Action]) {
+    board.step(&FrameInput {
+        pressed: inputs.to_vec(),
+        held: Vec::new(),
+    });
+}
+
+// the same on a copy of the board, for trying out several moves from one position
+pub fn play(board: &Board, inputs: &[Action]) -> Board {
+    let mut board: Board = format!("{board:#}").parse().unwrap();
+    tap(&mut board, inputs);
+    board
+}
+
+// compares everything Display shows, borders left out
+#[track_caller]
+pub fn assert_board(board: &Board, expected: &str) {
+    let actual = board.to_string();
+    assert_eq!(rows(&actual), rows(expected), "\n{actual}");
+}
+
+// same, but ignores the active piece. For after a lock, when the next piece comes from the randomizer
+#[track_caller]
+pub fn assert_locked(board: &Board, expected: &str) {
+    let actual = board.to_string();
+    let locked: Vec<_> = rows(&actual)
+        .into_iter()
+        .map(|row| row.replace('+', " "))
+        .collect();
+    assert_eq!(locked, rows(expected), "\n{actual}");
+}
@@ -0,0 +1,140 @@
+mod common;
+
+use common::{piece, tap};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use tetris_core::{
+    build_board, Action, Board, FinesseTarget, FinesseTrainer, Orientation, PieceType, Press,
+};
+
+// an O at the spawn position of an empty board, squares in columns 5 and 6
+fn o_board() -> Board {
+    let mut board = Board::with_seed(10, 20, common::SEED).unwrap();
+    board.set_active_piece(board.new_piece(PieceType::O));
+    board
+}
+
+#[test]
+fn das_to_the_wall_is_one_key() {
+    let board = o_board();
+    let start = board.active_piece();
+    let o_at = |x| piece(PieceType::O, Orientation::Deg0, (x, 1));
+    assert_eq!(board.finesse(start, &o_at(5)), Some(vec![]));
+    assert_eq!(
+        board.finesse(start, &o_at(4)),
+        Some(vec![Press::Tap(Action::MoveLeft)])
+    );
+    assert_eq!(
+        board.finesse(start, &o_at(0)),
+        Some(vec![Press::Das(Action::MoveLeft)])
+    );
+    // over to the wall and back beats four taps
+    assert_eq!(
+        board.finesse(start, &o_at(1)),
+        Some(vec![
+            Press::Das(Action::MoveLeft),
+            Press::Tap(Action::MoveRight)
+        ])
+    );
+    assert_eq!(
+        board.finesse(start, &o_at(8)),
+        Some(vec![Press::Das(Action::MoveRight)])
+    );
+}
+
+#[test]
+fn rotations_count_once_each() {
+    let mut board = Board::with_seed(10, 20, common::SEED).unwrap();
+    board.set_active_piece(board.new_piece(PieceType::T));
+    let start = board.active_piece().clone();
+    let upside_down = piece(PieceType::T, Orientation::Deg180, (5, 1));
+    assert_eq!(
+        board.finesse(&start, &upside_down),
+        Some(vec![Press::Tap(Action::Rotate180)])
+    );
+}
+
+#[test]
+fn counts_extra_keys() {
+    let mut board = o_board();
+    tap(&mut board, &[Action::MoveLeft; 5]);
+    tap(&mut board, &[Action::HardDrop]);
+    let finesse = board.last_finesse().unwrap();
+    assert_eq!(finesse.piece_number, 1);
+    assert_eq!(finesse.keys, [Action::MoveLeft; 5]);
+    assert_eq!(finesse.optimal, [Press::Das(Action::MoveLeft)]);
+    assert_eq!(finesse.faults(), 4);
+    assert_eq!(board.finesse_faults(), 4);
+
+    // the next piece starts from nothing
+    tap(&mut board, &[Action::HardDrop]);
+    assert_eq!(board.last_finesse().unwrap().faults(), 0);
+    assert_eq!(board.finesse_faults(), 4);
+}
+
+#[test]
+fn tucks_arent_judged() {
+    let mut board = common::board(
+        PieceType::I,
+        Orientation::Deg0,
+        "
+        |          |
+        |   ++++   |
+        |          |
+        |          |
+        |          |
+        |          |
+        |******    |
+        |          |",
+    );
+    let tuck = board
+        .placements(board.active_piece())
+        .into_iter()
+        .find(|placement| placement.inputs.contains(&Action::SoftDrop))
+        .unwrap();
+    tap(&mut board, &tuck.inputs);
+    assert_eq!(board.pieces_locked(), 1);
+    assert_eq!(board.last_finesse(), None);
+    assert_eq!(board.finesse_faults(), 0);
+}
+
+#[test]
+fn trainer_empties_the_board_after_every_piece() {
+    let mut board = Board::with_seed(10, 20, common::SEED).unwrap();
+    board.set_game_mode(&FinesseTrainer);
+    // nothing in the way, so anywhere the piece can go is a target
+    let targets = board.finesse_targets();
+    assert_eq!(targets.len(), board.placements(board.active_piece()).len());
+    assert!(targets
+        .iter()
+        .all(|target| board.finesse(board.active_piece(), target).is_some()));
+    for _ in 0..30 {
+        tap(&mut board, &[Action::HardDrop]);
+    }
+    assert!(!board.done());
+    assert_eq!(board.pieces_locked(), 30);
+    assert!((0..20).all(|y| (0..10).all(|x| !board.square_filled(x, y))));
+}
+
+#[test]
+fn targets_are_picked_once_per_piece() {
+    let mut board = build_board("", common::SEED, &FinesseTrainer);
+    let mut rng = ChaCha8Rng::seed_from_u64(1);
+    let mut target = FinesseTarget::default();
+    assert!(target.update(&board, &mut rng));
+    let picked = target.piece.clone().unwrap();
+    assert!(board.finesse_targets().contains(&picked));
+    assert!(!target.update(&board, &mut rng));
+    assert_eq!(target.piece, Some(picked.clone()));
+
+    // dropped right there it's a hit, then the next piece gets its own
+    board.set_active_piece(picked);
+    tap(&mut board, &[Action::HardDrop]);
+    assert!(target.hit(&board));
+    assert!(target.update(&board, &mut rng));
+    assert!(!target.hit(&board));
+    assert_eq!(
+        target.piece.unwrap().piece_type,
+        board.active_piece().piece_type
+    );
+}
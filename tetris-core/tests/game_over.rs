@@ -0,0 +1,57 @@
+mod common;
+
+use std::time::Duration;
+
+use tetris_core::{FrameInput, Orientation, PieceType, Sprint, Ultra};
+
+#[test]
+fn tops_out_when_the_next_piece_has_no_room() {
+    let mut board = common::board(
+        PieceType::I,
+        Orientation::Deg90,
+        "
+        |          |
+        |+  ****   |
+        |+         |
+        |+         |
+        |+         |
+        |          |",
+    );
+    board.do_instant_drop();
+    assert!(board.done());
+    assert!(!board.goal_reached());
+
+    // nothing moves any more
+    let piece = board.active_piece().clone();
+    board.do_instant_drop();
+    board.soft_drop();
+    assert!(!board.step(&FrameInput::default()));
+    assert_eq!(board.active_piece(), &piece);
+    assert_eq!(board.pieces_locked(), 1);
+}
+
+#[test]
+fn ultra_ends_after_two_minutes() {
+    let mut board = common::stack(&"|          |\n".repeat(20));
+    board.set_game_mode(&Ultra);
+    board.advance(Duration::from_secs(119));
+    assert!(!board.done());
+    board.advance(Duration::from_secs(1));
+    assert!(board.done());
+    assert!(board.goal_reached());
+}
+
+#[test]
+fn sprint_ends_on_the_fortieth_line() {
+    let mut board = common::stack(&("|     |\n".repeat(4) + &"|**** |\n".repeat(40)));
+    board.set_game_mode(&Sprint);
+    for _ in 0..10 {
+        assert!(!board.done());
+        let top = board.height() as i32 - 2;
+        board.set_active_piece(common::piece(PieceType::I, Orientation::Deg90, (4, top)));
+        board.do_instant_drop();
+    }
+    assert_eq!(board.lines_cleared(), 40);
+    assert!(board.done());
+    assert!(board.goal_reached());
+}
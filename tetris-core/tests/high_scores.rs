@@ -0,0 +1,118 @@
+use std::{collections::HashMap, io, time::Duration};
+
+use tetris_core::{HighScore, HighScores, Marathon, ScoreStorage, Sprint, MAX_HIGH_SCORES};
+
+// keeps everything in a map, or fails every write like a full disk
+#[derive(Default)]
+struct MemoryStorage {
+    values: HashMap<String, String>,
+    full: bool,
+}
+
+impl ScoreStorage for MemoryStorage {
+    fn read(&self, key: &str) -> Option<String> {
+        self.values.get(key).cloned()
+    }
+
+    fn write(&mut self, key: &str, value: &str) -> io::Result<()> {
+        if self.full {
+            return Err(io::Error::other("storage full"));
+        }
+        self.values.insert(key.to_owned(), value.to_owned());
+        Ok(())
+    }
+}
+
+fn high_score(score: u32, seconds: u64, date: u64) -> HighScore {
+    HighScore {
+        score,
+        lines: score / 100,
+        level: 1,
+        duration: Duration::from_secs(seconds),
+        date,
+        seed: date,
+    }
+}
+
+fn scores(high_scores: &HighScores) -> Vec<u32> {
+    high_scores
+        .entries()
+        .iter()
+        .map(|entry| entry.score)
+        .collect()
+}
+
+#[test]
+fn ranks_by_score_or_time() {
+    let storage = MemoryStorage::default();
+    let mut marathon = HighScores::load(&storage, &Marathon);
+    assert_eq!(marathon.add(high_score(500, 60, 1)), Some(0));
+    assert_eq!(marathon.add(high_score(900, 90, 2)), Some(0));
+    assert_eq!(marathon.add(high_score(700, 30, 3)), Some(1));
+    // a tie goes below the one that was there first
+    assert_eq!(marathon.add(high_score(700, 30, 4)), Some(2));
+    assert_eq!(scores(&marathon), [900, 700, 700, 500]);
+    assert_eq!(marathon.entries()[1].date, 3);
+
+    // sprint is a race, the quickest time wins whatever the score
+    let mut sprint = HighScores::load(&storage, &Sprint);
+    sprint.add(high_score(500, 60, 1));
+    sprint.add(high_score(900, 90, 2));
+    assert_eq!(sprint.add(high_score(100, 45, 3)), Some(0));
+    assert_eq!(scores(&sprint), [100, 500, 900]);
+}
+
+#[test]
+fn keeps_only_the_best() {
+    let mut high_scores = HighScores::load(&MemoryStorage::default(), &Marathon);
+    for score in 1..=MAX_HIGH_SCORES as u32 {
+        high_scores.add(high_score(score * 100, 60, score as u64));
+    }
+    assert_eq!(high_scores.entries().len(), MAX_HIGH_SCORES);
+    assert_eq!(high_scores.add(high_score(50, 60, 0)), None);
+    assert_eq!(high_scores.add(high_score(450, 60, 0)), Some(6));
+    assert_eq!(high_scores.entries().len(), MAX_HIGH_SCORES);
+    assert_eq!(high_scores.entries().last().unwrap().score, 200);
+}
+
+#[test]
+fn saves_and_loads_per_game_mode() {
+    let mut storage = MemoryStorage::default();
+    let mut high_scores = HighScores::load(&storage, &Marathon);
+    high_scores.add(high_score(1200, 95, 1700000000000));
+    high_scores.add(high_score(300, 20, 1700000001000));
+    high_scores.save(&mut storage).unwrap();
+    assert_eq!(
+        storage.values["highscores.marathon"],
+        "1200,12,1,95000,1700000000000,1700000000000\n300,3,1,20000,1700000001000,1700000001000"
+    );
+    let loaded = HighScores::load(&storage, &Marathon);
+    assert_eq!(loaded.entries(), high_scores.entries());
+    assert!(HighScores::load(&storage, &Sprint).entries().is_empty());
+}
+
+#[test]
+fn skips_lines_it_cant_read() {
+    let mut storage = MemoryStorage::default();
+    storage.values.insert(
+        "highscores.marathon".to_owned(),
+        "800,8,2,61500,5,6\nnot a score\n1,2,3\n700,7,1,1,2,-3\n600,6,1,30000,7,8".to_owned(),
+    );
+    let loaded = HighScores::load(&storage, &Marathon);
+    assert_eq!(scores(&loaded), [800, 600]);
+    assert_eq!(loaded.entries()[0].duration, Duration::from_millis(61500));
+    assert_eq!(loaded.entries()[0].level, 2);
+}
+
+#[test]
+fn save_errors_reach_the_caller() {
+    let mut storage = MemoryStorage {
+        full: true,
+        ..Default::default()
+    };
+    let mut high_scores = HighScores::load(&storage, &Marathon);
+    high_scores.add(high_score(100, 10, 1));
+    let error = high_scores.save(&mut storage).unwrap_err();
+    assert_eq!(error.to_string(), "storage full");
+    assert!(storage.values.is_empty());
+}
@@ -0,0 +1,98 @@
+mod common;
+
+use common::tap;
+use tetris_core::{Action, HoldRule, Orientation, PieceType};
+
+#[test]
+fn guideline_hold_respawns_once_per_piece() {
+    let mut board = common::board(
+        PieceType::T,
+        Orientation::Deg90,
+        "
+        next OI
+        |          |
+        |          |
+        |  +       |
+        |  ++      |
+        |  +       |
+        |          |",
+    );
+    assert!(board.can_hold());
+    board.hold_piece();
+    // the first hold takes the next piece, which comes in like any new piece
+    assert_eq!(board.active_piece(), &board.new_piece(PieceType::O));
+    assert_eq!(board.held_piece(), Some(&PieceType::T));
+    assert_eq!(board.next_pieces().next(), Some(&PieceType::I));
+    assert!(!board.can_hold());
+
+    // refused until a piece locks
+    board.hold_piece();
+    assert_eq!(board.active_piece().piece_type, PieceType::O);
+    assert_eq!(board.held_piece(), Some(&PieceType::T));
+    tap(&mut board, &[Action::HardDrop]);
+    assert!(board.can_hold());
+    assert_eq!(board.active_piece().piece_type, PieceType::I);
+
+    // after that it swaps with the held piece, the queue stays as it was
+    let next: Vec<_> = board.next_pieces().cloned().collect();
+    board.hold_piece();
+    assert_eq!(board.active_piece(), &board.new_piece(PieceType::T));
+    assert_eq!(board.active_piece().orientation, Orientation::Deg0);
+    assert_eq!(board.held_piece(), Some(&PieceType::I));
+    assert!(board.next_pieces().eq(next.iter()));
+    assert_eq!(board.stats().holds, 2);
+}
+
+#[test]
+fn swap_hold_stays_in_place_as_often_as_you_like() {
+    let mut board = common::board(
+        PieceType::T,
+        Orientation::Deg90,
+        "
+        next OI
+        |          |
+        |          |
+        |  +       |
+        |  ++      |
+        |  +       |
+        |          |",
+    );
+    board.set_hold_rule(HoldRule::Swap);
+    let t = board.active_piece().clone();
+    board.hold_piece();
+    assert_eq!(board.active_piece().piece_type, PieceType::O);
+    assert_eq!(board.active_piece().position, t.position);
+    assert_eq!(board.next_pieces().next(), Some(&PieceType::I));
+    for _ in 0..3 {
+        assert!(board.can_hold());
+        board.hold_piece();
+        assert_eq!(board.active_piece(), &t);
+        assert_eq!(board.held_piece(), Some(&PieceType::O));
+        board.hold_piece();
+        assert_eq!(board.active_piece().piece_type, PieceType::O);
+        assert_eq!(board.held_piece(), Some(&PieceType::T));
+    }
+    assert_eq!(board.next_pieces().next(), Some(&PieceType::I));
+}
+
+#[test]
+fn swap_hold_is_refused_when_the_other_piece_doesnt_fit() {
+    let mut board = common::board(
+        PieceType::I,
+        Orientation::Deg90,
+        "
+        next O
+        |          |
+        |****+*****|
+        |****+*****|
+        |****+*****|
+        |****+*****|",
+    );
+    board.set_hold_rule(HoldRule::Swap);
+    let i = board.active_piece().clone();
+    board.hold_piece();
+    assert_eq!(board.active_piece(), &i);
+    assert_eq!(board.held_piece(), None);
+    assert_eq!(board.next_pieces().next(), Some(&PieceType::O));
+    assert_eq!(board.stats().holds, 0);
+}
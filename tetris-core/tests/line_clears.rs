@@ -0,0 +1,142 @@
+mod common;
+
+use common::assert_locked;
+use tetris_core::{
+    build_board, Board, Endless, LineClear, NesScoring, Orientation, PieceType, Scoring, Square,
+    TSpin,
+};
+
+// an upright I above the well on the right
+fn drop_i(board: &mut Board) {
+    let top = board.height() as i32 - 2;
+    board.set_active_piece(common::piece(
+        PieceType::I,
+        Orientation::Deg90,
+        (board.width() as i32 - 1, top),
+    ));
+    board.do_instant_drop();
+}
+
+#[test]
+fn clears_score_by_the_number_of_lines() {
+    for (lines, points) in [(1, 100), (2, 300), (3, 500), (4, 800)] {
+        let mut fixture = "|     |\n".repeat(8 - lines);
+        fixture.push_str(&"|**** |\n".repeat(lines));
+        let mut board = common::stack(&fixture);
+        drop_i(&mut board);
+        let clear = board.last_clear().unwrap();
+        assert_eq!(clear.lines, lines as u32);
+        assert_eq!(
+            clear.name(),
+            ["SINGLE", "DOUBLE", "TRIPLE", "TETRIS"][lines - 1]
+        );
+        assert_eq!(board.lines_cleared(), lines as u32);
+        // plus 2 for each of the 4 rows the I fell
+        assert_eq!(board.score(), points + 2 * 4);
+    }
+}
+
+#[test]
+fn clears_rows_with_gaps_in_between() {
+    let mut board = common::stack(
+        "
+        |     |
+        |     |
+        |     |
+        |     |
+        |**** |
+        |* ** |
+        |**** |
+        |** * |",
+    );
+    drop_i(&mut board);
+    assert_eq!(board.last_clear().unwrap().lines, 2);
+    assert_locked(
+        &board,
+        "
+        |     |
+        |     |
+        |     |
+        |     |
+        |     |
+        |     |
+        |* ***|
+        |** **|",
+    );
+}
+
+#[test]
+fn back_to_back_tetrises_with_a_combo() {
+    let mut board = common::stack(&("|     |\n".repeat(4) + &"|**** |\n".repeat(8)));
+    drop_i(&mut board);
+    assert_eq!(board.score(), 800 + 2 * 8);
+    drop_i(&mut board);
+    let clear = board.last_clear().unwrap();
+    assert!(clear.back_to_back);
+    assert_eq!(clear.combo, 1);
+    assert_eq!(board.combo(), Some(1));
+    // 800 * 1.5 for back-to-back, 50 per combo
+    assert_eq!(board.score(), 816 + 1200 + 50 + 16);
+    assert_locked(&board, &"|     |\n".repeat(12));
+}
+
+#[test]
+fn placing_without_a_clear_ends_the_combo() {
+    let mut board = common::stack(
+        "
+        |     |
+        |     |
+        |     |
+        |     |
+        |     |
+        |     |
+        |     |
+        |**** |",
+    );
+    drop_i(&mut board);
+    assert_eq!(board.combo(), Some(0));
+    board.set_active_piece(common::piece(PieceType::O, Orientation::Deg0, (0, 6)));
+    board.do_instant_drop();
+    assert_eq!(board.combo(), None);
+}
+
+#[test]
+fn levels_go_up_every_ten_lines() {
+    let mut board = common::stack(&("|     |\n".repeat(4) + &"|**** |\n".repeat(12)));
+    drop_i(&mut board);
+    drop_i(&mut board);
+    assert_eq!(board.level(), 1);
+    drop_i(&mut board);
+    assert_eq!(board.lines_cleared(), 12);
+    assert_eq!(board.level(), 2);
+}
+
+#[test]
+fn nes_scoring_pays_by_nes_level() {
+    let clear = |lines, t_spin| LineClear {
+        lines,
+        t_spin,
+        back_to_back: true,
+        combo: 3,
+        piece_number: 1,
+    };
+    // no bonus for t-spins, back-to-backs or combos
+    for (lines, points) in [(0, 0), (1, 40), (2, 100), (3, 300), (4, 1200)] {
+        assert_eq!(NesScoring.line_clear(&clear(lines, TSpin::None), 0), points);
+        assert_eq!(
+            NesScoring.line_clear(&clear(lines, TSpin::Full), 9),
+            points * 10
+        );
+    }
+    assert_eq!((NesScoring.soft_drop(5), NesScoring.hard_drop(5)), (5, 0));
+
+    // levels count from 0 when the rules pick nes scoring, so the first single is worth 40
+    let mut board = build_board("scoring=nes", 1, &Endless);
+    assert_eq!(board.level(), 0);
+    for x in 0..9 {
+        board.set_square(x, 0, Square::Garbage);
+    }
+    drop_i(&mut board);
+    assert_eq!(board.lines_cleared(), 1);
+    assert_eq!(board.score(), 40);
+}
@@ -0,0 +1,136 @@
+mod common;
+
+use std::time::Duration;
+
+use common::tap;
+use tetris_core::{Action, Board, FrameInput, LockDelay, LockReset, Orientation, PieceType};
+
+// a T lying on the floor of an empty board
+fn t_on_the_floor(reset: LockReset) -> Board {
+    let mut board = common::board(
+        PieceType::T,
+        Orientation::Deg0,
+        "
+        |          |
+        |          |
+        |          |
+        |    +     |
+        |   +++    |",
+    );
+    board.set_lock_delay(LockDelay {
+        reset,
+        ..LockDelay::default()
+    });
+    board
+}
+
+// steps frames with nothing pressed until the piece locks, None if it doesn't within the limit
+fn frames_until_locked(board: &mut Board, limit: u32) -> Option<u32> {
+    (1..=limit).find(|_| {
+        board.step(&FrameInput::default());
+        board.pieces_locked() > 0
+    })
+}
+
+// presses one key and then the other every 10 frames, well inside the delay, until the piece locks
+fn wiggle(board: &mut Board, keys: [Action; 2]) -> u32 {
+    let mut frames = 0;
+    while board.pieces_locked() == 0 && frames < 3000 {
+        let pressed = if frames % 10 == 0 {
+            vec![keys[(frames / 10 % 2) as usize]]
+        } else {
+            vec![]
+        };
+        board.step(&FrameInput {
+            pressed,
+            held: Vec::new(),
+        });
+        frames += 1;
+    }
+    frames
+}
+
+#[test]
+fn moves_reset_the_timer_only_so_often() {
+    let mut board = t_on_the_floor(LockReset::Move);
+    // the first move comes before the timer starts, then 15 resets up to frame 150 and a full delay.
+    // 30 frames come to a hair under 500 ms, so it's the 31st
+    assert_eq!(
+        wiggle(&mut board, [Action::MoveLeft, Action::MoveRight]),
+        181
+    );
+
+    // turning kicks the T up off the floor each time, that mustn't start the timer over either
+    let mut board = t_on_the_floor(LockReset::Move);
+    let frames = wiggle(&mut board, [Action::RotateCW, Action::RotateCCW]);
+    assert_eq!(board.pieces_locked(), 1);
+    assert!(frames < 300, "{frames}");
+}
+
+#[test]
+fn step_reset_ignores_moves() {
+    let mut board = t_on_the_floor(LockReset::Step);
+    for _ in 0..20 {
+        tap(&mut board, &[Action::MoveLeft]);
+    }
+    // the same half second as if it had been left alone
+    assert_eq!(frames_until_locked(&mut board, 100), Some(11));
+}
+
+#[test]
+fn a_new_lowest_row_gives_a_fresh_timer() {
+    let mut board = common::board(
+        PieceType::O,
+        Orientation::Deg0,
+        "
+        |          |
+        |     ++   |
+        |     ++   |
+        |     ***  |
+        |     ***  |",
+    );
+    board.set_lock_delay(LockDelay {
+        reset: LockReset::Step,
+        ..LockDelay::default()
+    });
+    for _ in 0..20 {
+        board.step(&FrameInput::default());
+    }
+    // off the ledge, it falls with soft drop and lands on the floor with the whole delay again
+    tap(&mut board, &[Action::MoveLeft, Action::MoveLeft]);
+    board.soft_drop();
+    board.soft_drop();
+    assert!(board.is_grounded());
+    assert_eq!(board.pieces_locked(), 0);
+    assert_eq!(frames_until_locked(&mut board, 100), Some(31));
+}
+
+#[test]
+fn zero_delay_locks_on_landing() {
+    let mut board = t_on_the_floor(LockReset::Move);
+    board.set_lock_delay(LockDelay {
+        delay: Duration::ZERO,
+        ..LockDelay::default()
+    });
+    assert_eq!(frames_until_locked(&mut board, 100), Some(1));
+    // and a piece that's dropped locks on the same tick it lands
+    let mut board = common::stack(
+        "
+        |          |
+        |          |
+        |          |
+        |          |
+        |          |
+        |          |",
+    );
+    board.set_lock_delay(LockDelay {
+        delay: Duration::ZERO,
+        ..LockDelay::default()
+    });
+    while !board.is_grounded() {
+        board.soft_drop();
+    }
+    assert_eq!(board.pieces_locked(), 0);
+    board.tick();
+    assert_eq!(board.pieces_locked(), 1);
+}
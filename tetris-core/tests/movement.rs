@@ -0,0 +1,282 @@
+mod common;
+
+use common::{assert_board, assert_locked};
+use tetris_core::{
+    Action, Board, Direction, FrameInput, Gravity, GravityCurve, Orientation, PieceType,
+};
+
+#[test]
+fn fixture_prints_as_itself() {
+    let fixture = "
+        +------+
+        |      |
+        | +    |
+        |+++   |
+        |**  * |
+        +------+";
+    let board = common::board(PieceType::T, Orientation::Deg0, fixture);
+    assert_eq!(board.active_piece().position, (1, 1));
+    assert_board(&board, fixture);
+}
+
+#[test]
+fn moves_sideways_until_the_wall() {
+    let mut board = common::board(
+        PieceType::O,
+        Orientation::Deg0,
+        "
+        |  ++  |
+        |  ++  |
+        |      |",
+    );
+    assert!(board.move_piece(Direction::Left));
+    assert!(board.move_piece(Direction::Left));
+    assert!(!board.move_piece(Direction::Left));
+    assert_board(
+        &board,
+        "
+        |++    |
+        |++    |
+        |      |",
+    );
+    for _ in 0..4 {
+        assert!(board.move_piece(Direction::Right));
+    }
+    assert!(!board.move_piece(Direction::Right));
+    assert_board(
+        &board,
+        "
+        |    ++|
+        |    ++|
+        |      |",
+    );
+}
+
+#[test]
+fn locked_squares_block_moves() {
+    let mut board = common::board(
+        PieceType::L,
+        Orientation::Deg0,
+        "
+        |      |
+        | +++  |
+        |*  +* |",
+    );
+    assert!(!board.move_piece(Direction::Right));
+    assert!(!board.move_piece(Direction::Down));
+    assert!(board.move_piece(Direction::Left));
+    assert!(!board.move_piece(Direction::Left));
+    assert_board(
+        &board,
+        "
+        |      |
+        |+++   |
+        |* + * |",
+    );
+}
+
+#[test]
+fn soft_drop_scores_a_point_per_row() {
+    let mut board = common::board(
+        PieceType::I,
+        Orientation::Deg0,
+        "
+        | ++++ |
+        |      |
+        |      |",
+    );
+    board.soft_drop();
+    board.soft_drop();
+    assert_eq!(board.score(), 2);
+    assert_board(
+        &board,
+        "
+        |      |
+        |      |
+        | ++++ |",
+    );
+    board.soft_drop(); // on the floor, starts the lock delay instead
+    assert_eq!(board.score(), 2);
+    assert_eq!(board.pieces_locked(), 0);
+}
+
+#[test]
+fn hard_drop_locks_on_the_stack() {
+    let mut board = common::board(
+        PieceType::S,
+        Orientation::Deg0,
+        "
+        |        |
+        |  ++    |
+        |   ++   |
+        |        |
+        |        |
+        |        |
+        |   *    |",
+    );
+    let ghost = board.instant_drop_piece();
+    assert_eq!(ghost.position, (3, 2));
+    board.do_instant_drop();
+    assert_eq!(board.score(), 2 * 3);
+    assert_eq!(board.pieces_locked(), 1);
+    assert_locked(
+        &board,
+        "
+        |        |
+        |        |
+        |        |
+        |        |
+        |  **    |
+        |   **   |
+        |   *    |",
+    );
+}
+
+// instant_drop_piece searches from y = -1, pieces resting on the floor with their position in the
+// bottom row are only found by trying the row below it
+#[test]
+fn drops_to_the_bottom_row() {
+    for (piece_type, orientation, fixture) in [
+        (
+            PieceType::T,
+            Orientation::Deg0,
+            "
+            |  +   |
+            | +++  |
+            |      |
+            |      |",
+        ),
+        (
+            PieceType::I,
+            Orientation::Deg180,
+            "
+            | ++++ |
+            |      |
+            |      |
+            |      |",
+        ),
+        (
+            PieceType::L,
+            Orientation::Deg180,
+            "
+            | +    |
+            | +++  |
+            |      |
+            |      |",
+        ),
+    ] {
+        let mut board = common::board(piece_type, orientation, fixture);
+        let ghost = board.instant_drop_piece();
+        assert_eq!(ghost.position.1, 0);
+        board.do_instant_drop();
+        for (x, y) in ghost.squares() {
+            assert!(board.square_filled(x as usize, y as usize));
+        }
+    }
+}
+
+// vertical pieces hang below their position, so the ghost of an upright I already on the floor
+// has to stay put instead of going under the board
+#[test]
+fn vertical_i_on_the_floor() {
+    let mut board = common::board(
+        PieceType::I,
+        Orientation::Deg90,
+        "
+        |      |
+        |   +  |
+        |   +  |
+        |   +  |
+        |   +  |",
+    );
+    assert_eq!(board.active_piece().position, (3, 2));
+    assert_eq!(board.instant_drop_piece().position, (3, 2));
+    assert!(!board.move_piece(Direction::Down));
+    assert!(board.is_grounded());
+    board.do_instant_drop();
+    assert_eq!(board.score(), 0);
+    assert_locked(
+        &board,
+        "
+        |      |
+        |   *  |
+        |   *  |
+        |   *  |
+        |   *  |",
+    );
+}
+
+#[test]
+fn step_presses_and_holds() {
+    let mut board = common::board(
+        PieceType::O,
+        Orientation::Deg0,
+        "
+        |    ++    |
+        |    ++    |
+        |          |
+        |          |",
+    );
+    let tap = FrameInput {
+        pressed: vec![Action::MoveLeft],
+        held: vec![Action::MoveLeft],
+    };
+    assert!(board.step(&tap));
+    assert_eq!(board.active_piece().position.0, 3);
+    // keeps going after the auto shift delay
+    let hold = FrameInput {
+        pressed: Vec::new(),
+        held: vec![Action::MoveLeft],
+    };
+    for _ in 0..30 {
+        board.step(&hold);
+    }
+    assert_eq!(board.active_piece().position.0, 0);
+}
+
+// the piece's fall after this many frames with nothing pressed, and the gravity it fell at
+fn fall(level: u32, gravity_curve: GravityCurve, frames: u32) -> (i32, f32) {
+    let mut board = Board::with_seed(10, 40, common::SEED).unwrap();
+    board.set_gravity_curve(gravity_curve);
+    board.set_start_level(level);
+    let start = board.active_piece().position.1;
+    let idle = FrameInput {
+        pressed: Vec::new(),
+        held: Vec::new(),
+    };
+    for _ in 0..frames {
+        board.step(&idle);
+    }
+    (start - board.active_piece().position.1, board.gravity().0)
+}
+
+#[test]
+fn fractional_gravity_carries_over() {
+    // just under 1G skips a frame now and then, rather than dropping a cell every frame
+    let (fallen, gravity) = fall(13, GravityCurve::Guideline, 12);
+    assert!((0.9..1.).contains(&gravity), "{gravity}");
+    assert_eq!(fallen, 11);
+    // a bit over 1G doesn't round up to 2 cells a frame
+    let (fallen, gravity) = fall(14, GravityCurve::Guideline, 10);
+    assert!((1.4..1.5).contains(&gravity), "{gravity}");
+    assert_eq!(fallen, 14);
+    // whole frames per cell are still exact, NES level 0 drops every 48 frames
+    assert_eq!(fall(0, GravityCurve::Nes, 47).0, 0);
+    assert_eq!(fall(0, GravityCurve::Nes, 48).0, 1);
+    assert_eq!(fall(0, GravityCurve::Nes, 95).0, 1);
+    assert_eq!(fall(0, GravityCurve::Nes, 96).0, 2);
+}
+
+#[test]
+fn gravity_only_ever_gets_faster() {
+    for curve in [GravityCurve::Guideline, GravityCurve::Nes] {
+        let mut last = 0.;
+        for level in curve.first_level()..2000 {
+            let gravity = curve.gravity(level).0;
+            assert!(gravity > 0. && gravity.is_finite(), "{curve:?} {level}");
+            assert!(gravity >= last, "{curve:?} {level}");
+            last = gravity;
+        }
+    }
+    assert_eq!(GravityCurve::Guideline.gravity(116), Gravity::MAX);
+}
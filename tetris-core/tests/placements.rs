@@ -0,0 +1,151 @@
+mod common;
+
+use common::{assert_locked, play};
+use tetris_core::{Action, Board, Orientation, PieceType, TSpin};
+
+#[test]
+fn every_column_and_orientation_on_an_empty_board() {
+    let board = Board::with_seed(10, 20, common::SEED).unwrap();
+    let counts = [
+        (PieceType::I, 17),
+        (PieceType::O, 9),
+        (PieceType::T, 34),
+        (PieceType::S, 17),
+        (PieceType::Z, 17),
+        (PieceType::J, 34),
+        (PieceType::L, 34),
+    ];
+    for (piece_type, count) in counts {
+        let placements = board.placements(&board.new_piece(piece_type.clone()));
+        assert_eq!(placements.len(), count, "{piece_type:?}");
+        // one key to get anywhere in the middle, never more than a move per column and a rotation
+        assert_eq!(placements[0].inputs, [Action::HardDrop]);
+        assert!(placements
+            .iter()
+            .all(|placement| placement.inputs.len() <= 7));
+    }
+}
+
+#[test]
+fn every_placement_locks_where_it_says() {
+    let board = common::stack(
+        "
+        |          |
+        |          |
+        |          |
+        |          |
+        |          |
+        |          |
+        |  *       |
+        |  **   *  |
+        |* *** ** *|",
+    );
+    let placements = board.placements(board.active_piece());
+    assert!(!placements.is_empty());
+    for placement in placements {
+        let played = play(&board, &placement.inputs);
+        assert_eq!(played.pieces_locked(), 1, "{:?}", placement.inputs);
+        for (x, y) in placement.piece.squares() {
+            assert!(played.square_filled(x as usize, y as usize));
+        }
+    }
+}
+
+#[test]
+fn tucks_under_an_overhang() {
+    let board = common::board(
+        PieceType::I,
+        Orientation::Deg0,
+        "
+        |          |
+        |   ++++   |
+        |          |
+        |          |
+        |          |
+        |          |
+        |******    |
+        |          |",
+    );
+    let placement = board
+        .placements(board.active_piece())
+        .into_iter()
+        .find(|placement| {
+            placement
+                .piece
+                .squares()
+                .iter()
+                .all(|&(x, y)| x < 4 && y == 0)
+        })
+        .unwrap();
+    // quicker to go down standing up and turn at the bottom than to go round the overhang
+    assert_eq!(
+        placement.inputs,
+        [
+            Action::RotateCCW,
+            Action::SoftDrop,
+            Action::SoftDrop,
+            Action::SoftDrop,
+            Action::SoftDrop,
+            Action::RotateCCW,
+            Action::MoveLeft,
+            Action::MoveLeft,
+            Action::HardDrop,
+        ]
+    );
+    assert_locked(
+        &play(&board, &placement.inputs),
+        "
+        |          |
+        |          |
+        |          |
+        |          |
+        |          |
+        |          |
+        |******    |
+        |****      |",
+    );
+}
+
+#[test]
+fn spins_into_a_t_slot() {
+    let board = common::board(
+        PieceType::T,
+        Orientation::Deg0,
+        "
+        |    +     |
+        |   +++    |
+        |          |
+        |          |
+        |   *      |
+        |          |
+        |*** ******|
+        |***  *****|
+        |***  *****|",
+    );
+    let (placement, played) = board
+        .placements(board.active_piece())
+        .into_iter()
+        .map(|placement| {
+            let played = play(&board, &placement.inputs);
+            (placement, played)
+        })
+        .find(|(_, played)| played.lines_cleared() == 2)
+        .unwrap();
+    let clear = played.last_clear().unwrap();
+    assert_eq!(clear.t_spin, TSpin::Full);
+    let last_turn = placement.inputs[placement.inputs.len() - 2];
+    assert!(matches!(last_turn, Action::RotateCW | Action::RotateCCW));
+    assert_locked(
+        &played,
+        "
+        |          |
+        |          |
+        |          |
+        |          |
+        |          |
+        |          |
+        |   *      |
+        |          |
+        |**** *****|",
+    );
+}
@@ -0,0 +1,53 @@
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use tetris_core::{build_board, Board, Endless, PieceType, Randomizer, SevenBag};
+
+// every piece a board deals, hard dropping each one on a board tall enough not to top out
+fn dealt(board: &mut Board, count: usize) -> Vec<PieceType> {
+    (0..count)
+        .map(|_| {
+            let piece_type = board.active_piece().piece_type.clone();
+            board.do_instant_drop();
+            piece_type
+        })
+        .collect()
+}
+
+fn is_a_bag(pieces: &[PieceType]) -> bool {
+    let mut sorted = pieces.to_vec();
+    sorted.sort_by_key(|piece_type| PieceType::ALL.iter().position(|t| t == piece_type));
+    sorted == PieceType::ALL
+}
+
+#[test]
+fn seven_bag_deals_every_piece_once_per_bag() {
+    let mut rng = ChaCha8Rng::seed_from_u64(1);
+    let mut bag = SevenBag::new();
+    let pieces: Vec<_> = (0..70).map(|_| bag.next_piece(&mut rng)).collect();
+    assert!(pieces.chunks(7).all(is_a_bag));
+    // a fresh bag after a reset, not the rest of the old one
+    bag.next_piece(&mut rng);
+    bag.reset();
+    let pieces: Vec<_> = (0..7).map(|_| bag.next_piece(&mut rng)).collect();
+    assert!(is_a_bag(&pieces));
+}
+
+#[test]
+fn restarting_with_the_same_seed_deals_the_same_pieces() {
+    let mut board = Board::with_seed(10, 40, 3).unwrap();
+    let first = dealt(&mut board, 8);
+    board.restart(3);
+    assert_eq!(dealt(&mut board, 8), first);
+    assert!(is_a_bag(&first[..7]));
+    board.restart(4);
+    assert_ne!(dealt(&mut board, 8), first);
+}
+
+#[test]
+fn uniform_can_be_picked_in_the_rules() {
+    let mut board = build_board("randomizer=uniform", 3, &Endless);
+    // with this seed the first seven already repeat a piece, which a bag never would
+    assert!(!is_a_bag(&dealt(&mut board, 7)));
+    let mut board = build_board("", 3, &Endless);
+    assert!(is_a_bag(&dealt(&mut board, 7)));
+}
@@ -0,0 +1,117 @@
+use std::time::Duration;
+
+use tetris_core::{
+    build_board, Action, Board, Endless, FrameInput, Handling, Replay, MAX_REPLAY_FRAMES,
+};
+
+const HEADER: &str = "tetris replay 2\nseed 1\nmode Endless\nrules \nhandling 167000,33000,20\n";
+
+fn input(pressed: &[Action], held: &[Action]) -> FrameInput {
+    FrameInput {
+        pressed: pressed.to_vec(),
+        held: held.to_vec(),
+    }
+}
+
+// a made up game: a key every quarter second, moves held long enough for auto shift to kick in
+fn inputs() -> Vec<FrameInput> {
+    let keys = [
+        Action::MoveLeft,
+        Action::RotateCW,
+        Action::HardDrop,
+        Action::Hold,
+        Action::MoveRight,
+        Action::Rotate180,
+        Action::SoftDrop,
+        Action::HardDrop,
+    ];
+    (0..3000)
+        .map(|frame| {
+            let key = keys[frame / 15 % keys.len()];
+            let pressed: &[Action] = if frame % 15 == 0 { &[key] } else { &[] };
+            let held: &[Action] = if key.direction().is_some() {
+                &[key]
+            } else {
+                &[]
+            };
+            input(pressed, held)
+        })
+        .collect()
+}
+
+// sets the board up the way the front ends do and steps it through every frame
+fn play(replay: &Replay) -> Board {
+    let mut board = build_board(&replay.rules, replay.seed, &Endless);
+    board.set_handling(replay.handling.clone());
+    for frame in &replay.frames {
+        board.step(frame);
+    }
+    board
+}
+
+#[track_caller]
+fn assert_same_game(played: &Board, replayed: &Board) {
+    assert_eq!(format!("{replayed:#}"), format!("{played:#}"));
+    assert_eq!(replayed.score(), played.score());
+    assert_eq!(replayed.lines_cleared(), played.lines_cleared());
+    assert_eq!(replayed.stats(), played.stats());
+    assert_eq!(replayed.done(), played.done());
+}
+
+#[test]
+fn replays_give_the_same_game() {
+    let rules = "next=3&level=5";
+    let handling = Handling {
+        das: Duration::from_millis(100),
+        arr: Duration::ZERO,
+        soft_drop_factor: 40,
+    };
+    let mut board = build_board(rules, 9, &Endless);
+    board.set_handling(handling.clone());
+    let mut replay = Replay::new(9, "Endless", rules, handling);
+    for frame in inputs() {
+        board.step(&frame);
+        replay.record(frame);
+    }
+    assert!(board.pieces_locked() > 10);
+
+    assert_same_game(&board, &play(&replay));
+    let saved: Replay = replay.to_string().parse().unwrap();
+    assert_same_game(&board, &play(&saved));
+}
+
+#[test]
+fn text_round_trip() {
+    let mut replay = Replay::new(42, "Sprint", "rotation=ars&hold=swap", Handling::default());
+    let frames = [
+        (input(&[], &[]), 300),
+        (input(&[Action::MoveLeft], &[Action::MoveLeft]), 1),
+        (input(&[], &[Action::MoveLeft]), 9),
+        (input(&[Action::HardDrop, Action::Hold], &[]), 1),
+        (input(&[Action::Rotate180], &[]), 2),
+        (input(&[], &[]), 1),
+    ];
+    for (frame, count) in frames {
+        for _ in 0..count {
+            replay.record(frame.clone());
+        }
+    }
+    let text = replay.to_string();
+    assert!(
+        text.ends_with("frames /x300 0/0 /0x9 3.7/ 6/x2 /\n"),
+        "{text}"
+    );
+    assert_eq!(text.parse::<Replay>(), Ok(replay));
+}
+
+#[test]
+fn refuses_replays_longer_than_the_cap() {
+    let frames = |frames: &str| format!("{HEADER}frames {frames}").parse::<Replay>();
+    let replay = frames(&format!("/x{MAX_REPLAY_FRAMES}")).unwrap();
+    assert_eq!(replay.frames.len(), MAX_REPLAY_FRAMES);
+    let too_long = Err(format!("more than {MAX_REPLAY_FRAMES} frames"));
+    assert_eq!(frames(&format!("/x{}", MAX_REPLAY_FRAMES + 1)), too_long);
+    assert_eq!(frames("/x99999999999"), too_long);
+    // each run fits, but not all of them together
+    assert_eq!(frames(&format!("/x{MAX_REPLAY_FRAMES} 0/0")), too_long);
+}
@@ -0,0 +1,244 @@
+mod common;
+
+use common::{assert_board, assert_locked};
+use tetris_core::{Board, Orientation, PieceType, Rotation, RotationSystem, Square, Srs, TSpin};
+
+const ORIENTATIONS: [Orientation; 4] = [
+    Orientation::Deg0,
+    Orientation::Deg90,
+    Orientation::Deg180,
+    Orientation::Deg270,
+];
+
+fn rotate(board: &mut Board, rotation: &Rotation) {
+    match rotation {
+        Rotation::Clockwise => board.rotate_piece(true),
+        Rotation::CounterClockwise => board.rotate_piece(false),
+        Rotation::Half => board.rotate_piece_180(),
+    }
+}
+
+#[test]
+fn rotates_in_place_when_free() {
+    let mut board = common::board(
+        PieceType::T,
+        Orientation::Deg0,
+        "
+        |      |
+        |  +   |
+        | +++  |
+        |      |",
+    );
+    board.rotate_piece(true);
+    assert_board(
+        &board,
+        "
+        |      |
+        |  +   |
+        |  ++  |
+        |  +   |",
+    );
+    board.rotate_piece_180();
+    assert_board(
+        &board,
+        "
+        |      |
+        |  +   |
+        | ++   |
+        |  +   |",
+    );
+}
+
+#[test]
+fn i_kicks_off_the_left_wall() {
+    let mut board = common::board(
+        PieceType::I,
+        Orientation::Deg90,
+        "
+        |          |
+        |+         |
+        |+         |
+        |+         |
+        |+         |
+        |          |",
+    );
+    board.rotate_piece(true); // (0, 0) and (-1, 0) are in the wall, (2, 0) fits
+    assert_eq!(board.active_piece().orientation, Orientation::Deg180);
+    assert_board(
+        &board,
+        "
+        |          |
+        |          |
+        |++++      |
+        |          |
+        |          |
+        |          |",
+    );
+}
+
+#[test]
+fn i_kicks_off_the_right_wall() {
+    let mut board = common::board(
+        PieceType::I,
+        Orientation::Deg270,
+        "
+        |          |
+        |         +|
+        |         +|
+        |         +|
+        |         +|
+        |          |",
+    );
+    board.rotate_piece(false); // (-2, 0) is the first kick that fits
+    assert_eq!(board.active_piece().orientation, Orientation::Deg180);
+    assert_board(
+        &board,
+        "
+        |          |
+        |          |
+        |          |
+        |     ++++ |
+        |          |
+        |          |",
+    );
+}
+
+#[test]
+fn t_spin_double() {
+    let mut board = common::board(
+        PieceType::T,
+        Orientation::Deg90,
+        "
+        |          |
+        |          |
+        |* +*******|
+        |* ++******|
+        |**+*******|",
+    );
+    board.rotate_piece(true);
+    board.do_instant_drop();
+    let clear = board.last_clear().unwrap();
+    assert_eq!((clear.lines, &clear.t_spin), (2, &TSpin::Full));
+    assert_eq!(board.score(), 1200);
+    assert_locked(
+        &board,
+        "
+        |          |
+        |          |
+        |          |
+        |          |
+        |*  *******|",
+    );
+}
+
+// SRS only counts a t-spin as full with one front corner open when it took the last kick
+#[test]
+fn t_spin_double_through_the_last_kick() {
+    let mut board = common::board(
+        PieceType::T,
+        Orientation::Deg0,
+        "
+        |          |
+        |          |
+        |   *+     |
+        |   +++    |
+        |*** ******|
+        |***  *****|
+        |***  *****|",
+    );
+    board.rotate_piece(true); // (0, 0) to (0, -2) are blocked, (-1, -2) drops it into the slot
+    assert_eq!(board.active_piece().position, (3, 1));
+    board.do_instant_drop();
+    let clear = board.last_clear().unwrap();
+    assert_eq!((clear.lines, &clear.t_spin), (2, &TSpin::Full));
+    assert_locked(
+        &board,
+        "
+        |          |
+        |          |
+        |          |
+        |          |
+        |   *      |
+        |          |
+        |**** *****|",
+    );
+}
+
+// tries every kick of every SRS table: the kicks before it get blocked by a square that's in the way
+// of that kick only, then the rotation has to end up exactly at the kick's offset
+#[test]
+fn every_srs_kick() {
+    let start = (6, 8);
+    let mut tested = 0;
+    let mut unreachable = 0;
+    for piece_type in PieceType::ALL {
+        for from in ORIENTATIONS {
+            for rotation in [
+                Rotation::Clockwise,
+                Rotation::CounterClockwise,
+                Rotation::Half,
+            ] {
+                let kicks = Srs.kicks(&piece_type, &from, &rotation);
+                let current = common::piece(piece_type.clone(), from.clone(), start).squares();
+                let target_orientation = from.rotate(&rotation);
+                let squares_at = |(dx, dy): (i32, i32)| {
+                    common::piece(
+                        piece_type.clone(),
+                        target_orientation.clone(),
+                        (start.0 + dx, start.1 + dy),
+                    )
+                    .squares()
+                };
+                'kicks: for (kick, &(dx, dy)) in kicks.iter().enumerate() {
+                    let target = squares_at((dx, dy));
+                    let mut blockers = Vec::new();
+                    for &earlier in &kicks[..kick] {
+                        let Some(blocker) = squares_at(earlier)
+                            .into_iter()
+                            .find(|square| !target.contains(square) && !current.contains(square))
+                        else {
+                            // the earlier kick only needs squares the piece is on or this kick
+                            // needs too, so it always wins and this kick can't ever happen
+                            unreachable += 1;
+                            continue 'kicks;
+                        };
+                        blockers.push(blocker);
+                    }
+
+                    let mut board = Board::with_seed(12, 16, common::SEED).unwrap();
+                    for &(x, y) in &blockers {
+                        board.set_square(x as usize, y as usize, Square::Garbage);
+                    }
+                    board.set_active_piece(common::piece(piece_type.clone(), from.clone(), start));
+                    rotate(&mut board, &rotation);
+                    let piece = board.active_piece();
+                    assert_eq!(
+                        (&piece.orientation, piece.position),
+                        (&target_orientation, (start.0 + dx, start.1 + dy)),
+                        "{piece_type:?} {from:?} {rotation:?} kick {kick}\n{board}"
+                    );
+                    tested += 1;
+                }
+            }
+        }
+    }
+    assert_eq!((tested, unreachable), (368, 28));
+}
+
+#[test]
+fn rotation_fails_when_every_kick_is_blocked() {
+    let fixture = "
+        |**********|
+        |**+++*****|
+        |***+******|
+        |**********|";
+    let mut board = common::board(PieceType::T, Orientation::Deg180, fixture);
+    for rotation in [
+        Rotation::Clockwise,
+        Rotation::CounterClockwise,
+        Rotation::Half,
+    ] {
+        rotate(&mut board, &rotation);
+        assert_board(&board, fixture);
+    }
+}
@@ -0,0 +1,117 @@
+mod common;
+
+use std::time::Duration;
+
+use common::tap;
+use tetris_core::{
+    format_seconds, format_time, Action, Board, LineClear, Orientation, PieceType, Stats, TSpin,
+};
+
+fn clear(lines: u32, t_spin: TSpin, back_to_back: bool, combo: u32) -> LineClear {
+    LineClear {
+        lines,
+        t_spin,
+        back_to_back,
+        combo,
+        piece_number: 1,
+    }
+}
+
+#[test]
+fn counts_pieces_keys_and_holds() {
+    let mut board = Board::with_seed(10, 20, common::SEED).unwrap();
+    board.set_active_piece(board.new_piece(PieceType::T));
+    tap(
+        &mut board,
+        &[Action::MoveLeft, Action::RotateCW, Action::HardDrop],
+    );
+    tap(&mut board, &[Action::Hold]);
+    tap(&mut board, &[Action::Hold]); // once per piece, but the key still counts
+    tap(&mut board, &[Action::HardDrop]);
+    let stats = board.stats();
+    assert_eq!(stats.pieces, 2);
+    assert_eq!(stats.pieces_of(&PieceType::T), 1);
+    assert_eq!(stats.piece_types.iter().sum::<u32>(), 2);
+    assert_eq!(stats.keys, 6);
+    assert_eq!(stats.holds, 1);
+    assert_eq!(stats.time, board.play_time());
+    assert_eq!(board.pieces_locked(), 2);
+}
+
+#[test]
+fn t_spins_are_kept_apart_from_other_clears() {
+    let mut board = common::board(
+        PieceType::T,
+        Orientation::Deg90,
+        "
+        |          |
+        |          |
+        |* +*******|
+        |* ++******|
+        |**+*******|",
+    );
+    board.rotate_piece(true);
+    board.do_instant_drop();
+    let stats = board.stats();
+    assert_eq!(stats.t_spins, [0, 0, 1, 0]);
+    assert_eq!(stats.doubles, 0);
+    assert_eq!(stats.attack, 4);
+
+    let mut board = common::stack(
+        "
+        |     |
+        |     |
+        |     |
+        |     |
+        |**** |
+        |**** |
+        |**** |
+        |**** |",
+    );
+    board.set_active_piece(common::piece(PieceType::I, Orientation::Deg90, (4, 6)));
+    board.do_instant_drop();
+    assert_eq!(board.stats().tetrises, 1);
+    assert_eq!(board.stats().attack, 4);
+}
+
+#[test]
+fn attack_follows_the_guideline_table() {
+    let mut stats = Stats::default();
+    let attacks = [
+        (clear(1, TSpin::None, false, 0), 0),
+        (clear(2, TSpin::None, false, 0), 1),
+        (clear(4, TSpin::None, true, 0), 5),
+        (clear(1, TSpin::Mini, false, 0), 0),
+        (clear(0, TSpin::Full, false, 0), 0),
+        (clear(3, TSpin::Full, true, 0), 7),
+        (clear(1, TSpin::None, false, 2), 1),
+        (clear(1, TSpin::None, false, 30), 5),
+    ];
+    for (clear, attack) in attacks {
+        let before = stats.attack;
+        stats.add_clear(&clear);
+        assert_eq!(stats.attack - before, attack, "{clear:?}");
+    }
+    assert_eq!((stats.singles, stats.doubles, stats.tetrises), (3, 1, 1));
+    assert_eq!(stats.t_spins, [1, 1, 0, 1]);
+}
+
+#[test]
+fn rates() {
+    let mut stats = Stats::default();
+    assert_eq!((stats.pps(), stats.apm(), stats.kpp()), (0., 0., 0.));
+    stats.pieces = 30;
+    stats.keys = 90;
+    stats.attack = 12;
+    stats.time = Duration::from_secs(20);
+    assert_eq!((stats.pps(), stats.apm(), stats.kpp()), (1.5, 36., 3.));
+}
+
+#[test]
+fn formats_times() {
+    let time = Duration::from_millis(83_456);
+    assert_eq!(format_seconds(time), "1:23");
+    assert_eq!(format_time(time), "1:23.45");
+    assert_eq!(format_time(Duration::from_secs(3600)), "60:00.00");
+    assert_eq!(format_seconds(Duration::ZERO), "0:00");
+}
@@ -0,0 +1,149 @@
+use tetris_core::{Board, Orientation, PieceType, Square};
+
+const PUZZLE: &str = "seed 7
+hold T
+next SZIOLJ
+orientation 90
++------+
+|      |
+|   i  |
+|   i  |
+|   i  |
+|G  iLL|
+|GZZ OO|
+|GJZZOO|
++------+
+";
+
+#[test]
+fn letters_round_trip() {
+    let board: Board = PUZZLE.parse().unwrap();
+    assert_eq!(format!("{board:#}"), PUZZLE);
+
+    assert_eq!(board.seed(), 7);
+    assert_eq!(board.held_piece(), Some(&PieceType::T));
+    let next: Vec<_> = board.next_pieces().cloned().collect();
+    assert_eq!(
+        next,
+        [
+            PieceType::S,
+            PieceType::Z,
+            PieceType::I,
+            PieceType::O,
+            PieceType::L
+        ]
+    );
+    let piece = board.active_piece();
+    assert_eq!(piece.piece_type, PieceType::I);
+    assert_eq!(piece.orientation, Orientation::Deg90);
+    assert_eq!(piece.position, (3, 4));
+    assert_eq!(board.get_square(0, 0), Some(&Square::Garbage));
+    assert_eq!(board.get_square(1, 0), Some(&Square::Piece(PieceType::J)));
+    assert_eq!(board.get_square_hue(4, 2), Some(PieceType::L.to_hue()));
+    assert_eq!(board.get_square(1, 2), None);
+}
+
+#[test]
+fn played_games_round_trip() {
+    let mut board = Board::with_seed(10, 20, 3).unwrap();
+    board.hold_piece();
+    for _ in 0..5 {
+        board.rotate_piece(true);
+        board.do_instant_drop();
+    }
+    let text = format!("{board:#}");
+    let parsed: Board = text.parse().unwrap();
+    assert_eq!(format!("{parsed:#}"), text);
+    assert_eq!(parsed.to_string(), board.to_string());
+}
+
+#[test]
+fn reads_plain_display_output() {
+    let mut board = Board::with_seed(6, 6, 1).unwrap();
+    board.do_instant_drop();
+    let parsed: Board = board.to_string().parse().unwrap();
+    assert_eq!(parsed.to_string(), board.to_string());
+    assert_eq!(
+        parsed.active_piece().piece_type,
+        board.active_piece().piece_type
+    );
+    // without letters the colours are gone
+    assert!((0..6).any(|x| parsed.get_square(x, 0) == Some(&Square::Garbage)));
+}
+
+#[test]
+fn spawns_the_first_next_piece_without_an_active_piece() {
+    let board: Board = "next OT\n|     |\n|     |\n|     |\n|* ***|"
+        .parse()
+        .unwrap();
+    assert_eq!(board.active_piece().piece_type, PieceType::O);
+    assert_eq!(board.next_pieces().next(), Some(&PieceType::T));
+    assert!(!board.done());
+}
+
+#[test]
+fn bad_boards_say_what_is_wrong() {
+    for (text, error) in [
+        ("", "no playfield"),
+        ("|  |\n|   |", "row 2 isn't 2 wide"),
+        ("|X    |\n|     |\n|     |", "unknown piece 'X'"),
+        (
+            "|oo   |\n|ll   |\n|     |",
+            "the active piece has two different letters",
+        ),
+        (
+            "|+    |\n| +   |\n|     |",
+            "the active squares don't make a piece",
+        ),
+        ("hold IO\n|  |", "hold takes one piece, not \"IO\""),
+        ("next IOTJLSZ\n|  |", "at most 6 next pieces"),
+        ("orientation 45\n|  |", "bad orientation \"45\""),
+        ("speed 3\n|  |", "unknown line \"speed 3\""),
+    ] {
+        assert_eq!(
+            text.parse::<Board>().err().as_deref(),
+            Some(error),
+            "{text}"
+        );
+    }
+}
+
+#[test]
+fn boards_too_small_for_a_piece_are_refused() {
+    for (text, error) in [
+        (
+            "|    |",
+            "a 4x1 board is too small for every piece to spawn",
+        ),
+        (
+            "|  |\n|  |",
+            "a 2x2 board is too small for every piece to spawn",
+        ),
+        (
+            "|    |\n|    |\n|    |",
+            "a 4x3 board is too small for every piece to spawn",
+        ),
+        (
+            "|     |\n|     |",
+            "a 5x2 board is too small for every piece to spawn",
+        ),
+    ] {
+        assert_eq!(
+            text.parse::<Board>().err().as_deref(),
+            Some(error),
+            "{text}"
+        );
+    }
+    assert!(Board::with_seed(4, 20, 1).is_err());
+    assert!(Board::with_seed(10, 0, 1).is_err());
+
+    // just big enough, pieces lock and the game ends without going off the board
+    let mut board = Board::with_seed(10, 3, 1).unwrap();
+    for _ in 0..10 {
+        board.do_instant_drop();
+    }
+    assert!(board.done());
+    let mut board: Board = "|     |\n|     |\n|     |".parse().unwrap();
+    board.do_instant_drop();
+    assert!(board.done());
+}
@@ -0,0 +1,218 @@
+// draws the whole screen, two terminal columns per square so they come out about square
+use std::io;
+
+use crossterm::{
+    cursor::MoveTo,
+    queue,
+    style::{Color, Print, ResetColor, SetForegroundColor},
+    terminal::{BeginSynchronizedUpdate, Clear, ClearType, EndSynchronizedUpdate},
+};
+use tetris_core::{
+    format_seconds, format_time, Goal, Orientation, PieceType, Ranking, RotationSystem, Stats,
+};
+
+use crate::{keys, Game};
+
+const BLOCK: &str = "██";
+const GHOST: &str = "░░";
+const TARGET: &str = "▒▒"; // where the finesse trainer wants the piece, the ghost goes over it
+const BOARD_X: u16 = 12; // the left panel goes before it
+
+// same colours as the web build, which draws the middle of a block as hsl(hue, 100%, 50%)
+fn hue_to_color(hue: f32, lightness: f32) -> Color {
+    let chroma = 1. - (2. * lightness - 1.).abs();
+    let sector = hue.rem_euclid(360.) / 60.;
+    let x = chroma * (1. - (sector % 2. - 1.).abs());
+    let (r, g, b) = match sector as u32 {
+        0 => (chroma, x, 0.),
+        1 => (x, chroma, 0.),
+        2 => (0., chroma, x),
+        3 => (0., x, chroma),
+        4 => (x, 0., chroma),
+        _ => (chroma, 0., x),
+    };
+    let m = lightness - chroma / 2.;
+    let channel = |value: f32| ((value + m) * 255.).round() as u8;
+    Color::Rgb {
+        r: channel(r),
+        g: channel(g),
+        b: channel(b),
+    }
+}
+
+fn square(out: &mut impl io::Write, (x, y): (u16, u16), hue: f32, text: &str) -> io::Result<()> {
+    queue!(
+        out,
+        MoveTo(x, y),
+        SetForegroundColor(hue_to_color(hue, 0.5)),
+        Print(text),
+        ResetColor
+    )
+}
+
+fn text(out: &mut impl io::Write, (x, y): (u16, u16), text: &str) -> io::Result<()> {
+    queue!(out, MoveTo(x, y), Print(text))
+}
+
+// spawn orientation, in a 4x3 box with its top left at (x, y)
+fn piece_preview(
+    out: &mut impl io::Write,
+    (x, y): (u16, u16),
+    piece_type: &PieceType,
+    rotation_system: &dyn RotationSystem,
+) -> io::Result<()> {
+    for (dx, dy) in rotation_system.shape(piece_type, &Orientation::Deg0) {
+        let column = x as i32 + 2 * (dx + 1);
+        let row = y as i32 + 1 - dy;
+        if column >= 0 && row >= 0 {
+            square(out, (column as u16, row as u16), piece_type.to_hue(), BLOCK)?;
+        }
+    }
+    Ok(())
+}
+
+// a column of its own right of the next queue, rates first
+fn stats_column(out: &mut impl io::Write, x: u16, stats: &Stats) -> io::Result<()> {
+    let [t_spin_zeros, t_spin_singles, t_spin_doubles, t_spin_triples] = stats.t_spins;
+    let lines = [
+        format!("PPS    {:.2}", stats.pps()),
+        format!("APM    {:.1}", stats.apm()),
+        format!("KPP    {:.2}", stats.kpp()),
+        format!("PIECES {}", stats.pieces),
+        format!("KEYS   {}", stats.keys),
+        format!("HOLDS  {}", stats.holds),
+        String::new(),
+        format!("SINGLE {}", stats.singles),
+        format!("DOUBLE {}", stats.doubles),
+        format!("TRIPLE {}", stats.triples),
+        format!("TETRIS {}", stats.tetrises),
+        format!("T-SPIN {t_spin_zeros}/{t_spin_singles}/{t_spin_doubles}/{t_spin_triples}"),
+    ];
+    text(out, (x, 1), "STATS")?;
+    for (row, line) in (2..).zip(&lines) {
+        text(out, (x, row), line)?;
+    }
+    // how many of each piece, the letter in the piece's colour
+    for (row, piece_type) in (lines.len() as u16 + 3..).zip(PieceType::ALL) {
+        square(
+            out,
+            (x, row),
+            piece_type.to_hue(),
+            &piece_type.letter().to_string(),
+        )?;
+        text(out, (x + 2, row), &stats.pieces_of(&piece_type).to_string())?;
+    }
+    Ok(())
+}
+
+pub fn draw(out: &mut impl io::Write, game: &Game) -> io::Result<()> {
+    let board = &game.board;
+    let width = board.width() as u16;
+    let height = board.height() as u16;
+    queue!(out, BeginSynchronizedUpdate, Clear(ClearType::All))?;
+
+    // playfield with a border, row 0 of the board at the bottom
+    let right = BOARD_X + 1 + 2 * width;
+    text(
+        out,
+        (BOARD_X, 0),
+        &format!("┌{}┐", "─".repeat(2 * width as usize)),
+    )?;
+    for row in 1..=height {
+        text(out, (BOARD_X, row), "│")?;
+        text(out, (right, row), "│")?;
+    }
+    text(
+        out,
+        (BOARD_X, height + 1),
+        &format!("└{}┘", "─".repeat(2 * width as usize)),
+    )?;
+    let screen = |(x, y): (i32, i32)| (BOARD_X + 1 + 2 * x as u16, height - y as u16);
+    for y in 0..board.height() {
+        for x in 0..board.width() {
+            if let Some(hue) = board.get_square_hue(x, y) {
+                square(out, screen((x as i32, y as i32)), hue, BLOCK)?;
+            }
+        }
+    }
+    if !board.done() {
+        let hue = board.active_piece().piece_type.to_hue();
+        if let Some(target) = &game.target.piece {
+            for position in target.squares() {
+                square(out, screen(position), hue, TARGET)?;
+            }
+        }
+        for position in board.instant_drop_piece().squares() {
+            square(out, screen(position), hue, GHOST)?;
+        }
+        for position in board.active_piece().squares() {
+            if board.in_range(position) {
+                square(out, screen(position), hue, BLOCK)?;
+            }
+        }
+    }
+
+    // hold and stats on the left
+    text(out, (1, 1), "HOLD")?;
+    if let Some(held_piece) = board.held_piece() {
+        piece_preview(out, (1, 2), held_piece, board.rotation_system())?;
+    }
+    let stats = [
+        ("SCORE", board.score().to_string()),
+        ("LEVEL", board.level().to_string()),
+        ("LINES", board.lines_cleared().to_string()),
+        ("TIME", format_seconds(board.play_time())),
+        ("FINESSE", board.finesse_faults().to_string()),
+    ];
+    for (row, (name, value)) in (6..).step_by(2).zip(stats) {
+        text(out, (1, row), name)?;
+        text(out, (1, row + 1), &value)?;
+    }
+    let goal = match board.game_mode().goal() {
+        Goal::Lines(lines) => format!("{lines} lines"),
+        Goal::Time(time) => format!("{} s", time.as_secs()),
+        Goal::None => String::new(),
+    };
+    text(out, (1, 17), board.game_mode().name())?;
+    text(out, (1, 18), &goal)?;
+    if let Some(clear) = board.last_clear() {
+        if clear.piece_number == board.pieces_locked() {
+            text(out, (1, 20), &clear.name())?;
+        }
+    }
+
+    // next queue on the right, then whatever the game is waiting for
+    let panel = right + 3;
+    text(out, (panel, 1), "NEXT")?;
+    let mut row = 2;
+    for piece_type in board.next_pieces() {
+        piece_preview(out, (panel, row), piece_type, board.rotation_system())?;
+        row += 3;
+    }
+    stats_column(out, panel + 10, board.stats())?;
+    row += 1;
+    if board.done() {
+        let title = if board.goal_reached() {
+            "FINISHED"
+        } else {
+            "GAME OVER"
+        };
+        text(out, (panel, row), title)?;
+        let result = match board.game_mode().ranking() {
+            Ranking::Time if board.goal_reached() => format_time(board.play_time()),
+            _ => format!("{} points", board.score()),
+        };
+        text(out, (panel, row + 1), &result)?;
+        text(
+            out,
+            (panel, row + 2),
+            &format!("{:.2} PPS", board.stats().pps()),
+        )?;
+        text(out, (panel, row + 3), "r: play again")?;
+    } else if game.paused {
+        text(out, (panel, row), "PAUSED")?;
+    }
+    text(out, (1, height + 2), keys::HELP)?;
+    queue!(out, EndSynchronizedUpdate)?;
+    out.flush()
+}
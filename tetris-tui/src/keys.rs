@@ -0,0 +1,36 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use tetris_core::Action;
+
+// the web build's default bindings, minus the keys terminals don't report on their own (shift, ctrl)
+pub fn action(key: &KeyEvent) -> Option<Action> {
+    let action = match key.code {
+        KeyCode::Left => Action::MoveLeft,
+        KeyCode::Right => Action::MoveRight,
+        KeyCode::Down => Action::SoftDrop,
+        KeyCode::Up => Action::RotateCW,
+        KeyCode::Esc => Action::Pause,
+        KeyCode::Char(letter) => match letter.to_ascii_lowercase() {
+            ' ' | 's' => Action::HardDrop,
+            'x' => Action::RotateCW,
+            'z' => Action::RotateCCW,
+            'a' => Action::Rotate180,
+            'c' | 'e' => Action::Hold,
+            'p' => Action::Pause,
+            'r' => Action::Restart,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(action)
+}
+
+pub fn is_quit(key: &KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Char('q') => true,
+        KeyCode::Char('c') => key.modifiers.contains(KeyModifiers::CONTROL),
+        _ => false,
+    }
+}
+
+pub const HELP: &str =
+    "←→ move  ↓ soft drop  space hard drop  ↑/x z a rotate  c hold  p pause  r restart  q quit";
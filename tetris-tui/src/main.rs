@@ -0,0 +1,238 @@
+// plays in a terminal, with the same rules strings and game modes as the web build:
+//     tetris-tui --mode sprint --rules "rotation=ars&next=3" --seed 42
+use std::{
+    env,
+    io::{self, stdout, Write},
+    mem, process,
+    time::Instant,
+};
+
+use crossterm::{
+    cursor::{Hide, Show},
+    event::{
+        self, Event, KeyEvent, KeyEventKind, KeyboardEnhancementFlags, PopKeyboardEnhancementFlags,
+        PushKeyboardEnhancementFlags,
+    },
+    execute,
+    terminal::{
+        disable_raw_mode, enable_raw_mode, supports_keyboard_enhancement, EnterAlternateScreen,
+        LeaveAlternateScreen,
+    },
+};
+use tetris_core::{
+    build_board, Action, Board, Endless, FinesseTarget, FrameInput, GameMode, FRAME_DURATION,
+    GAME_MODES, MAX_CATCH_UP_FRAMES,
+};
+
+mod draw;
+mod keys;
+
+const USAGE: &str =
+    "usage: tetris-tui [--mode marathon|sprint|ultra|endless|finesse] [--rules RULES] [--seed SEED]
+RULES are the web build's url parameters, e.g. \"rotation=ars&hold=swap&lock=300\"";
+
+struct Options {
+    game_mode: &'static dyn GameMode,
+    rules: String,
+    seed: Option<u64>, // same seed every game, otherwise random
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        game_mode: &Endless,
+        rules: String::new(),
+        seed: None,
+    };
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("{arg} needs a value"));
+        match arg.as_str() {
+            "--mode" => {
+                let name = value()?;
+                options.game_mode = GAME_MODES
+                    .into_iter()
+                    .find(|mode| mode.name().eq_ignore_ascii_case(&name))
+                    .ok_or(format!("unknown mode {name:?}"))?;
+            }
+            "--rules" => options.rules = value()?,
+            "--seed" => {
+                let seed = value()?;
+                options.seed = Some(seed.parse().map_err(|_| format!("bad seed {seed:?}"))?);
+            }
+            "--help" | "-h" => {
+                println!("{USAGE}");
+                process::exit(0);
+            }
+            _ => return Err(format!("unknown argument {arg:?}")),
+        }
+    }
+    Ok(options)
+}
+
+pub struct Game {
+    pub board: Board,
+    pub paused: bool,
+    pub target: FinesseTarget,
+    seed: Option<u64>,
+    pressed: Vec<Action>,
+    held: Vec<Action>,
+    // terminals without the kitty keyboard protocol only send presses and their own key repeats,
+    // so nothing counts as held and every repeat moves once. With it the board does DAS itself
+    reports_releases: bool,
+}
+
+impl Game {
+    // false when the player wants to quit
+    fn key(&mut self, key: &KeyEvent) -> bool {
+        if keys::is_quit(key) {
+            return false;
+        }
+        let Some(action) = keys::action(key) else {
+            return true;
+        };
+        match key.kind {
+            KeyEventKind::Release => self.held.retain(|held| *held != action),
+            KeyEventKind::Repeat if self.reports_releases => {}
+            KeyEventKind::Press | KeyEventKind::Repeat => self.press(action),
+        }
+        true
+    }
+
+    fn press(&mut self, action: Action) {
+        match action {
+            Action::Pause if !self.board.done() => {
+                self.paused = !self.paused;
+                self.held.clear(); // releases while paused would get lost
+            }
+            Action::Restart => self.restart(),
+            _ if self.paused || self.board.done() => {}
+            _ => {
+                self.pressed.push(action);
+                if self.reports_releases && action.direction().is_some() {
+                    self.held.push(action);
+                }
+            }
+        }
+    }
+
+    fn step(&mut self) -> bool {
+        if self.paused || self.board.done() {
+            return false;
+        }
+        let input = FrameInput {
+            pressed: mem::take(&mut self.pressed),
+            held: self.held.clone(),
+        };
+        let changed = self.board.step(&input);
+        self.pick_target();
+        changed
+    }
+
+    fn pick_target(&mut self) {
+        if self.board.game_mode().clears_every_piece() {
+            self.target.update(&self.board, &mut rand::thread_rng());
+        }
+    }
+
+    fn restart(&mut self) {
+        self.board.restart(self.seed.unwrap_or_else(rand::random));
+        self.target = FinesseTarget::default();
+        self.pick_target();
+        self.pressed.clear();
+        self.held.clear();
+        self.paused = false;
+    }
+}
+
+// raw mode and the alternate screen for as long as it's alive, even when the game panics
+struct Terminal {
+    reports_releases: bool,
+}
+
+impl Terminal {
+    fn enter() -> io::Result<Self> {
+        enable_raw_mode()?;
+        let reports_releases = supports_keyboard_enhancement().unwrap_or(false);
+        execute!(stdout(), EnterAlternateScreen, Hide)?;
+        if reports_releases {
+            execute!(
+                stdout(),
+                PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::REPORT_EVENT_TYPES)
+            )?;
+        }
+        Ok(Self { reports_releases })
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        if self.reports_releases {
+            let _ = execute!(stdout(), PopKeyboardEnhancementFlags);
+        }
+        let _ = execute!(stdout(), Show, LeaveAlternateScreen);
+        let _ = disable_raw_mode();
+    }
+}
+
+// same fixed 60 Hz steps as the web build, waiting for keys in between
+fn run(game: &mut Game, out: &mut impl Write) -> io::Result<()> {
+    let mut next_frame = Instant::now();
+    let mut shown_second = None;
+    let mut redraw = true;
+    loop {
+        if redraw {
+            draw::draw(out, game)?;
+            redraw = false;
+        }
+        while let Some(timeout) = next_frame.checked_duration_since(Instant::now()) {
+            if !event::poll(timeout)? {
+                break;
+            }
+            match event::read()? {
+                Event::Key(key) => {
+                    if !game.key(&key) {
+                        return Ok(());
+                    }
+                    redraw = true;
+                }
+                Event::Resize(..) => redraw = true,
+                _ => {}
+            }
+        }
+        if next_frame.elapsed() > FRAME_DURATION * MAX_CATCH_UP_FRAMES {
+            next_frame = Instant::now();
+        }
+        next_frame += FRAME_DURATION;
+        redraw |= game.step();
+        // the clock on screen still has to tick when nothing moves
+        let second = game.board.play_time().as_secs();
+        if shown_second != Some(second) {
+            shown_second = Some(second);
+            redraw = true;
+        }
+    }
+}
+
+fn main() -> io::Result<()> {
+    let options = match parse_args(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("{error}\n{USAGE}");
+            process::exit(2);
+        }
+    };
+    let seed = options.seed.unwrap_or_else(rand::random);
+    let board = build_board(&options.rules, seed, options.game_mode);
+
+    let terminal = Terminal::enter()?;
+    let mut game = Game {
+        board,
+        paused: false,
+        target: FinesseTarget::default(),
+        seed: options.seed,
+        pressed: Vec::new(),
+        held: Vec::new(),
+        reports_releases: terminal.reports_releases,
+    };
+    game.pick_target();
+    run(&mut game, &mut stdout())
+}